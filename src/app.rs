@@ -10,30 +10,44 @@ use crate::screen::info_screen;
 use crate::screen::reann_screen;
 use crate::screen::rm_screen;
 use crate::screen::search_screen;
-use crossterm::event::{self, Event, KeyCode, KeyEvent, KeyEventKind, KeyModifiers};
+use crossterm::event::{Event, KeyCode, KeyEvent, KeyEventKind, KeyModifiers};
 use ratatui::text::ToText;
 use ratatui::{DefaultTerminal, Frame, Terminal};
 use std::ascii::AsciiExt;
 use std::cmp::PartialEq;
-use std::sync::mpsc::{TryRecvError, channel};
-use std::sync::{Arc, Mutex};
+use std::sync::Arc;
 use std::time::Duration;
-use std::{io, thread};
+use std::io;
 use crate::app::Screen::SearchInfo;
 use crate::client::http_client::HttpClient;
+use crate::client::rpc_client::RpcClient;
 use crate::config::Config;
-use crate::config::ConfigKeyBindingKey::{KbAdd, KbDel, KbDownload, KbHelp, KbHome, KbInfo, KbQuit, KbReAnn, KbSearch};
+use crate::event::{AppEvent, EventLoop};
+use crate::poller::Poller;
+use crate::rate_history::RateHistory;
+use crate::store::TorrentStore;
+use crate::config::ConfigKeyBindingKey::{KbAdd, KbDel, KbDownload, KbGroups, KbHelp, KbHome, KbInfo, KbLimits, KbMove, KbProfiles, KbQuit, KbReAnn, KbSearch, KbSettings, KbStats};
 use crate::service::torrent_service::TorrentService;
 use crate::service::transmission_service::TransmissionService;
 use crate::screen::add_screen::AddScreen;
+use crate::screen::groups_screen::GroupsScreen;
 use crate::screen::help_screen::HelpScreen;
 use crate::screen::home_screen::HomeScreen;
-use crate::screen::info_screen::{InfoScreen, InfoScreenArgs};
+use crate::screen::info_screen::InfoScreen;
+use crate::screen::limits_popup::LimitsPopup;
+use crate::screen::move_popup::MovePopup;
+use crate::screen::popup_screen::{Notifier, PopupLevel, PopupScreen};
+use crate::screen::profiles_screen::ProfilesScreen;
 use crate::screen::reann_screen::{ReannScreen, ReannScreenArgs};
 use crate::screen::rm_screen::{RmScreen, RmScreenArgs};
 use crate::screen::search_info_screen::{SearchInfoScreen, SearchInfoScreenArgs};
 use crate::screen::search_res_screen::{SearchResArgs, SearchResScreen};
 use crate::screen::search_screen::SearchScreen;
+use crate::screen::settings_screen::SettingsScreen;
+use crate::screen::stats_screen::StatsScreen;
+
+// how often the loop wakes up without any input (e.g. to expire toasts)
+const TICK_RATE: Duration = Duration::from_millis(250);
 
 pub trait KeyEventHandler {
     /*
@@ -56,29 +70,28 @@ pub struct EmptyRenderableArgs {}
 
 impl RenderableArgs for EmptyRenderableArgs {}
 
-#[derive(PartialEq)]
-pub enum Screen { Home, Help, Add, ReAnn, Del, Info, Search, SearchRes, SearchInfo, Popup }
+#[derive(Clone, Copy, PartialEq)]
+pub enum Screen { Home, Help, Add, ReAnn, Del, Info, Search, SearchRes, SearchInfo, Profiles, Stats, Settings, Groups }
 
 struct AppState {
-    screen: Screen,
-    popup_msg: String
+    screen: Screen
 }
 
 impl AppState {
     pub fn new(screen: Screen) -> Self {
-        Self { screen, popup_msg: String::new() }
+        Self { screen }
     }
 }
 
 pub struct App {
     config: Config,
-    terminal: Arc<Mutex<DefaultTerminal>>,
+    terminal: DefaultTerminal,
     state: AppState,
 }
 
 impl App {
 
-    pub fn new(config: Config, terminal: Arc<Mutex<DefaultTerminal>>) -> Self {
+    pub fn new(config: Config, terminal: DefaultTerminal) -> Self {
         Self {
             config,
             terminal,
@@ -87,112 +100,153 @@ impl App {
     }
 
     pub fn run(&mut self) -> io::Result<()> {
-        if !TransmissionService::transmission_daemon_is_active() {
-            println!("transmission-daemon does not look active");
-            return Ok(());
-        }
-
         let runtime = tokio::runtime::Builder::new_current_thread()
                 .enable_all()
                 .build()?;
         let http_client = HttpClient::new(runtime);
         let torrent_service_arc = Arc::new(TorrentService::new(http_client));
+        let rpc_client = RpcClient::from_connection(&self.config.connection()).map_err(io::Error::other)?;
+        // an unreachable daemon does not stop the UI, the `Poller` backs off and Home shows why
+        let transmission_service_arc = Arc::new(TransmissionService::new(rpc_client));
+
         let config_values = self.config.values();
         let key_bindings = config_values.key_bindings();
 
-        let home_screen_arc = Arc::new(Mutex::new(HomeScreen::new(key_bindings.clone())));
-        let info_screen_arc = Arc::new(Mutex::new(InfoScreen::new(key_bindings.clone())));
+        // one queue for input, ticks and daemon snapshots
+        let event_loop = EventLoop::new(TICK_RATE);
+        let torrent_store = TorrentStore::default();
+        let rate_history = RateHistory::default();
+        let poller = Poller::spawn(
+            transmission_service_arc.clone(),
+            torrent_store.clone(),
+            rate_history.clone(),
+            event_loop.sender(),
+            self.config.refresh_interval()
+        );
+
+        // popups are drawn over every screen
+        let notifier = Notifier::default();
+        let mut popup_screen = PopupScreen::new(notifier.clone());
+
+        let mut home_screen = HomeScreen::new(key_bindings.clone(), transmission_service_arc.clone(), notifier.clone(), poller.clone(), torrent_store.clone(), rate_history.clone(), self.config.sort());
+        let mut info_screen = InfoScreen::new(key_bindings.clone(), transmission_service_arc.clone(), notifier.clone(), poller.clone(), torrent_store.clone(), rate_history.clone());
         let mut help_screen = HelpScreen::new(key_bindings.clone());
-        let mut add_screen = AddScreen::new(key_bindings.clone());
-        let mut reann_screen = ReannScreen::new(key_bindings.clone());
-        let mut del_screen = RmScreen::new(key_bindings.clone());
-        let mut search_screen = SearchScreen::new(key_bindings.clone(), torrent_service_arc.clone());
-        let mut search_res_screen = SearchResScreen::new(key_bindings.clone(), torrent_service_arc.clone());
+        let mut add_screen = AddScreen::new(key_bindings.clone(), transmission_service_arc.clone(), notifier.clone());
+        let mut reann_screen = ReannScreen::new(key_bindings.clone(), transmission_service_arc.clone(), notifier.clone(), torrent_store.clone());
+        let mut del_screen = RmScreen::new(key_bindings.clone(), transmission_service_arc.clone(), notifier.clone(), torrent_store.clone());
+        let mut search_screen = SearchScreen::new(key_bindings.clone(), torrent_service_arc.clone(), notifier.clone());
+        let mut search_res_screen = SearchResScreen::new(key_bindings.clone(), torrent_service_arc.clone(), transmission_service_arc.clone(), notifier.clone());
         let mut search_info_screen = SearchInfoScreen::new(key_bindings.clone());
+        let mut stats_screen = StatsScreen::new(key_bindings.clone());
+        let mut settings_screen = SettingsScreen::new(key_bindings.clone(), transmission_service_arc.clone(), notifier.clone());
+        let mut groups_screen = GroupsScreen::new(
+            key_bindings.clone(),
+            transmission_service_arc.clone(),
+            notifier.clone(),
+            poller.clone(),
+            torrent_store.clone()
+        );
+        // drawn over Home or Info
+        let mut limits_popup = LimitsPopup::new(transmission_service_arc.clone(), notifier.clone(), poller.clone());
+        let mut move_popup = MovePopup::new(
+            transmission_service_arc.clone(),
+            notifier.clone(),
+            poller.clone(),
+            torrent_store.clone(),
+            self.config.recent_dirs()
+        );
+        let mut profiles_screen = ProfilesScreen::new(
+            key_bindings.clone(),
+            poller.clone(),
+            notifier.clone(),
+            event_loop.sender(),
+            config_values.profiles(),
+            self.config.profile().to_string()
+        );
 
         loop {
-            let (tx, rx) = channel();
-            let home_screen_arc_clone = home_screen_arc.clone();
-            let info_screen_arc_clone = info_screen_arc.clone();
-            let terminal_clone = self.terminal.clone();
-
-            // home page (torrent list) needs refreshing, info page (torrent info) needs refreshing
-            match self.state.screen {
-                Screen::Home | Screen::Info => {
-                    let is_home = self.state.screen == Screen::Home;
-                    thread::spawn(move || {
-                        loop {
-                            let _ = terminal_clone.lock().unwrap().draw(|frame| {
-                                match is_home {
-
-                                    // Home screen
-                                    true => home_screen_arc_clone.lock().unwrap().render(frame, EmptyRenderableArgs::default()),
-
-                                    // Info screen
-                                    _ => {
-                                        let selected_torrent = home_screen_arc_clone.lock().unwrap().active_row_torrent();
-                                        info_screen_arc_clone
-                                            .lock()
-                                            .unwrap()
-                                            .render(frame, InfoScreenArgs::new(selected_torrent))
-                                    }
-                                }
-                            });
-                            thread::sleep(Duration::from_millis(3000));
+            // screens render only what they already have, nothing in here talks to the daemon
+            self.terminal.draw(|frame| {
+                match self.state.screen {
+                    Screen::Home => home_screen.render(frame, EmptyRenderableArgs::default()),
+                    Screen::Info => info_screen.render(frame, EmptyRenderableArgs::default()),
+                    Screen::Help => help_screen.render(frame, EmptyRenderableArgs::default()),
+                    Screen::Add => add_screen.render(frame, EmptyRenderableArgs::default()),
+                    Screen::Search => search_screen.render(frame, EmptyRenderableArgs::default()),
+                    Screen::Profiles => profiles_screen.render(frame, EmptyRenderableArgs::default()),
+                    Screen::Stats => stats_screen.render(frame, EmptyRenderableArgs::default()),
+                    Screen::Settings => settings_screen.render(frame, EmptyRenderableArgs::default()),
+                    Screen::Groups => groups_screen.render(frame, EmptyRenderableArgs::default()),
+                    Screen::Del => del_screen.render(frame, RmScreenArgs::new(home_screen.target_ids())),
+                    Screen::ReAnn => reann_screen.render(frame, ReannScreenArgs::new(home_screen.target_ids())),
+                    Screen::SearchRes => {
+                        search_res_screen.render(frame, SearchResArgs::new(search_screen.get_state().get_results()))
+                    } Screen::SearchInfo => {
+                        search_info_screen.render(frame, SearchInfoScreenArgs::new(search_res_screen.active_row_torrent()))
+                    }
+                }
+                if limits_popup.is_open() {
+                    limits_popup.render(frame, EmptyRenderableArgs::default());
+                }
+                if move_popup.is_open() {
+                    move_popup.render(frame, EmptyRenderableArgs::default());
+                }
+                popup_screen.render(frame, EmptyRenderableArgs::default());
+            })?;
 
-                            // thread control
-                            match rx.try_recv() {
-                                Ok(_) | Err(TryRecvError::Disconnected) => {
-                                    break;
-                                }
-                                Err(TryRecvError::Empty) => {}
-                            }
-                        }
-                    });
-                } _ => {
-                    let selected_index = home_screen_arc_clone.lock().unwrap().active_row();
-                    let _ = terminal_clone
-                        .lock()
-                        .unwrap()
-                        .draw(|frame|
-                            match self.state.screen {
-                                Screen::SearchRes => {
-                                    search_res_screen.render(frame, SearchResArgs::new(search_screen.get_state().get_results()))
-                                } Screen::SearchInfo => {
-                                   search_info_screen.render(frame, SearchInfoScreenArgs::new(search_res_screen.active_row_torrent()))
-                                } Screen::Popup => {
-                                   // TODO
-                                } Screen::Del | Screen::ReAnn => {
-                                    match self.state.screen {
-                                        Screen::Del => del_screen.render(frame, RmScreenArgs::new(selected_index)),
-                                        Screen::ReAnn => reann_screen.render(frame, ReannScreenArgs::new(selected_index)),
-                                        _ => {}
-                                    }
-                                } _ => {
-                                    match self.state.screen {
-                                        Screen::Help => help_screen.render(frame, EmptyRenderableArgs::default()),
-                                        Screen::Add => add_screen.render(frame, EmptyRenderableArgs::default()),
-                                        Screen::Search => search_screen.render(frame, EmptyRenderableArgs::default()),
-                                        Screen::SearchRes => search_res_screen.render(frame, SearchResArgs::new(search_screen.get_state().get_results())),
-                                        _ => {}
-                                    }
-                                }
-                            }
-                        );
+            let event = match event_loop.next()? {
+                AppEvent::Input(event) => event,
+                AppEvent::Snapshot(snapshot) => {
+                    home_screen.update(snapshot.torrents);
+                    if let Some(torrent) = snapshot.focused_torrent {
+                        info_screen.update(torrent);
+                    }
+                    if let Some(session_stats) = snapshot.session_stats {
+                        stats_screen.update(session_stats);
+                    }
+                    continue;
+                }
+                AppEvent::ProfileProbe { profile, result } => {
+                    profiles_screen.update(&profile, result);
+                    continue;
+                }
+                // nothing changed but time, redraw (e.g. to expire toasts)
+                AppEvent::Tick => {
+                    if self.state.screen == Screen::Profiles {
+                        profiles_screen.tick();
+                    }
+                    continue;
                 }
+            };
+            match event {
+                // back off while nobody is looking
+                Event::FocusLost => poller.terminal_focus(false),
+                Event::FocusGained => poller.terminal_focus(true),
+                _ => {}
             }
-
-            let event = event::read()?;
             if let Event::Key(key_event) = event {
                 let ctrl = key_event.modifiers.contains(KeyModifiers::CONTROL);
 
-                // terminate spawned thread (in case of home page)
-                if self.state.screen != Screen::Home {
-                    let _ = tx.send(());
+                // a modal popup takes every key until acknowledged
+                if notifier.has_modal() {
+                    popup_screen.handle_key_event(key_event, event);
+                    continue;
+                }
+                // so do the limits and move popups until closed
+                if limits_popup.is_open() {
+                    limits_popup.handle_key_event(key_event, event);
+                    continue;
+                }
+                if move_popup.is_open() {
+                    move_popup.handle_key_event(key_event, event);
+                    // remember the destinations for the next run
+                    if move_popup.recent_dirs() != self.config.recent_dirs() && let Err(e) = self.config.save_recent_dirs(move_popup.recent_dirs().to_vec()) {
+                        notifier.modal(PopupLevel::Error, e.to_string());
+                    }
+                    continue;
                 }
 
-                let home_screen_arc_clone_2 = home_screen_arc.clone();
-                let info_screen_arc_clone_2 = info_screen_arc.clone();
+                let previous_screen = self.state.screen;
 
                 // switch main screens or exit
                 if key_event.kind == KeyEventKind::Press {
@@ -202,6 +256,19 @@ impl App {
                             else if c == *key_bindings.get(&KbAdd).unwrap() { self.state.screen = Screen::Add }
                             else if c == *key_bindings.get(&KbSearch).unwrap() { self.state.screen = Screen::Search }
                             else if c == *key_bindings.get(&KbHelp).unwrap() { self.state.screen = Screen::Help }
+                            else if c == *key_bindings.get(&KbStats).unwrap() { self.state.screen = Screen::Stats }
+                            else if c == *key_bindings.get(&KbSettings).unwrap() {
+                                settings_screen.load();
+                                self.state.screen = Screen::Settings
+                            }
+                            else if c == *key_bindings.get(&KbGroups).unwrap() {
+                                groups_screen.load(home_screen.target_ids());
+                                self.state.screen = Screen::Groups
+                            }
+                            else if c == *key_bindings.get(&KbProfiles).unwrap() {
+                                profiles_screen.refresh();
+                                self.state.screen = Screen::Profiles
+                            }
                             else if c == *key_bindings.get(&KbQuit).unwrap() { break }
                         },
                         _ => {}
@@ -213,30 +280,32 @@ impl App {
                         match key_event.code {
                             // switch to subscreen
                             KeyCode::Char(c) if ctrl => {
-                                if c == *key_bindings.get(&KbDel).unwrap() { self.state.screen = Screen::Del }
+                                if c == *key_bindings.get(&KbDel).unwrap() {
+                                    del_screen.reset();
+                                    self.state.screen = Screen::Del
+                                }
                                 else if c == *key_bindings.get(&KbReAnn).unwrap() { self.state.screen = Screen::ReAnn }
                                 else if c == *key_bindings.get(&KbInfo).unwrap() { self.state.screen = Screen::Info }
+                                else if c == *key_bindings.get(&KbLimits).unwrap() { limits_popup.open(home_screen.target_ids()) }
+                                else if c == *key_bindings.get(&KbMove).unwrap() { move_popup.open(home_screen.target_ids()) }
                                 else {
-                                    home_screen_arc_clone_2
-                                        .lock()
-                                        .unwrap()
-                                        .handle_key_event(key_event, event);
+                                    home_screen.handle_key_event(key_event, event);
                                 }
                             }
                             _ => {
-                                home_screen_arc_clone_2
-                                    .lock()
-                                    .unwrap()
-                                    .handle_key_event(key_event, event);
+                                home_screen.handle_key_event(key_event, event);
                             }
                         }
+                        // remember the sort order for the next run
+                        if home_screen.sort() != self.config.sort() && let Err(e) = self.config.save_sort(home_screen.sort()) {
+                            notifier.modal(PopupLevel::Error, e.to_string());
+                        }
                     } Screen::SearchRes => {
                         match key_event.code {
                             // switch to subscreen
                             KeyCode::Char(c) if ctrl => {
                                 if c == *key_bindings.get(&KbInfo).unwrap() { self.state.screen = Screen::SearchInfo }
-                                else if c == *key_bindings.get(&KbDownload).unwrap() {
-                                    search_res_screen.handle_key_event(key_event, event);
+                                else if c == *key_bindings.get(&KbDownload).unwrap() && !search_res_screen.handle_key_event(key_event, event) {
                                     self.state.screen = Screen::Home
                                 }
                             }
@@ -267,19 +336,72 @@ impl App {
                             self.state.screen = Screen::Home; // return to home if we are done from this screen
                         }
                     } Screen::Info => {
-                        if !info_screen_arc_clone_2
-                            .lock()
-                            .unwrap()
-                            .handle_key_event(key_event, event)
-                        {
+                        match key_event.code {
+                            KeyCode::Char(c) if ctrl && c == *key_bindings.get(&KbLimits).unwrap() => {
+                                limits_popup.open(info_screen.torrent_id().into_iter().collect());
+                            }
+                            KeyCode::Char(c) if ctrl && c == *key_bindings.get(&KbMove).unwrap() => {
+                                move_popup.open(info_screen.torrent_id().into_iter().collect());
+                            }
+                            _ => {
+                                if !info_screen.handle_key_event(key_event, event) {
+                                    self.state.screen = Screen::Home; // return to home if we are done from this screen
+                                }
+                            }
+                        }
+                    } Screen::Stats => {
+                        if !stats_screen.handle_key_event(key_event, event) {
+                            self.state.screen = Screen::Home; // return to home if we are done from this screen
+                        }
+                    } Screen::Settings => {
+                        if !settings_screen.handle_key_event(key_event, event) {
+                            self.state.screen = Screen::Home; // return to home if we are done from this screen
+                        }
+                    } Screen::Groups => {
+                        if !groups_screen.handle_key_event(key_event, event) {
+                            self.state.screen = Screen::Home; // return to home if we are done from this screen
+                        }
+                    } Screen::Profiles => {
+                        let active_profile = profiles_screen.active_profile().to_string();
+                        if !profiles_screen.handle_key_event(key_event, event) {
+                            // what Home shows is only stale once another daemon is connected
+                            if profiles_screen.active_profile() != active_profile {
+                                home_screen.reset();
+                            }
                             self.state.screen = Screen::Home; // return to home if we are done from this screen
                         }
-                    } Screen::Popup => {
-
                     }
                 }
+
+                if self.state.screen != previous_screen {
+                    self.on_screen_change(previous_screen, &home_screen, &mut info_screen, &poller);
+                }
             }
         }
         Ok(())
     }
+
+    /*
+        Let the poller know what we are looking at: the Info screen needs the details of the selected
+        torrent, the Stats screen the session statistics, and coming back to Home after an action (add, remove, switching daemon, ...) should
+        show its outcome right away.
+    */
+    fn on_screen_change(&self, previous_screen: Screen, home_screen: &HomeScreen, info_screen: &mut InfoScreen, poller: &Poller) {
+        if self.state.screen == Screen::Info {
+            let torrent = home_screen.active_row_torrent();
+            poller.focus(torrent.as_ref().map(|torrent| torrent.id));
+            // show what the list already knows until the details arrive
+            info_screen.update(Ok(torrent.unwrap_or_default()));
+        } else if previous_screen == Screen::Info {
+            poller.focus(None);
+        }
+        // any command makes the poller poll right away, only a plain return to Home asks for it
+        if self.state.screen == Screen::Stats {
+            poller.watch_session_stats(true);
+        } else if previous_screen == Screen::Stats {
+            poller.watch_session_stats(false);
+        } else if self.state.screen == Screen::Home && previous_screen != Screen::Info {
+            poller.refresh();
+        }
+    }
 }
@@ -1,19 +1,19 @@
 use std::process::Command;
+use crate::error::{RustorError, RustorResult};
 
 #[derive(Default, Clone, Copy)]
 pub struct CliClient {}
 
 impl CliClient {
 
-    pub fn run_command(command: String) -> Vec<String> {
-        let cmd = command.clone();
+    pub fn run_command(command: String) -> RustorResult<Vec<String>> {
         let status_command = Command::new("sh")
             .arg("-c")
-            .arg(command)
+            .arg(&command)
             .output()
-            .expect(format!("failed to execute `{}`", cmd).as_str())
+            .map_err(|e| RustorError::Command(format!("failed to execute `{}`: {}", command, e)))?
             .stdout;
 
-        vec![String::from_utf8(status_command).unwrap_or_default()]
+        Ok(vec![String::from_utf8(status_command).unwrap_or_default()])
     }
 }
\ No newline at end of file
@@ -1,6 +1,6 @@
 use serde::de::DeserializeOwned;
-use std::io::Error;
 use tokio::runtime::Runtime;
+use crate::error::{RustorError, RustorResult};
 
 pub struct HttpClient {
     runtime: Runtime
@@ -12,15 +12,15 @@ impl HttpClient {
         Self { runtime }
     }
 
-    pub fn request<T>(&self, url: String) -> Result<T, Error>
+    pub fn request<T>(&self, url: String) -> RustorResult<T>
     where T: DeserializeOwned {
         self.runtime.block_on(async {
             match reqwest::get(url).await {
-                Ok(response) => match response.json::<T>().await {
-                    Ok(dto) => Ok(dto),
-                    Err(e) => Err(Error::other(e))
+                Ok(response) => match response.text().await {
+                    Ok(body) => serde_json::from_str(&body).map_err(|e| RustorError::Parse(e.to_string())),
+                    Err(e) => Err(RustorError::Transport(e.to_string()))
                 },
-                Err(e) => { Err(Error::other(e)) }
+                Err(e) => { Err(RustorError::Transport(e.to_string())) }
             }
         })
     }
@@ -1,2 +1,5 @@
 pub mod http_client;
-pub mod cli_client;
\ No newline at end of file
+pub mod cli_client;
+pub mod rpc_client;
+#[cfg(test)]
+pub mod stub_daemon;
@@ -0,0 +1,184 @@
+use reqwest::{Client, Response, StatusCode, Url};
+use serde::de::DeserializeOwned;
+use std::net::IpAddr;
+use std::sync::Mutex;
+use std::time::Duration;
+use tokio::runtime::{Builder, Runtime};
+use crate::config::ConnectionValues;
+use crate::dto::transmission_dto::TransmissionRequest;
+use crate::error::{RustorError, RustorResult};
+
+const SESSION_ID_HEADER: &str = "X-Transmission-Session-Id";
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(3);
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(30);
+
+pub struct RpcClient {
+    runtime: Runtime,
+    client: Client,
+    url: String,
+    credentials: Option<(String, String)>,
+    session_id: Mutex<String>
+}
+
+impl RpcClient {
+
+    pub fn new(runtime: Runtime, url: String, credentials: Option<(String, String)>) -> Self {
+        let client = Client::builder()
+            .connect_timeout(CONNECT_TIMEOUT)
+            .timeout(REQUEST_TIMEOUT)
+            .build()
+            .unwrap_or_default();
+        Self { runtime, client, url, credentials, session_id: Mutex::new(String::new()) }
+    }
+
+    pub fn from_connection(connection: &ConnectionValues) -> RustorResult<Self> {
+        let runtime = Builder::new_current_thread()
+            .enable_all()
+            .build()
+            .map_err(|e| RustorError::Transport(e.to_string()))?;
+        Ok(Self::new(runtime, connection.url(), connection.credentials()))
+    }
+
+    // Whether the daemon runs on this host, i.e. its filesystem is ours
+    pub fn is_local(&self) -> bool {
+        let host = Url::parse(&self.url).ok().and_then(|url| url.host_str().map(str::to_string)).unwrap_or_default();
+        match host.trim_start_matches('[').trim_end_matches(']').parse::<IpAddr>() {
+            Ok(ip) => ip.is_loopback(),
+            Err(_) => host.eq_ignore_ascii_case("localhost")
+        }
+    }
+
+    /*
+        Transmission rejects any request without a valid `X-Transmission-Session-Id` header with a
+        `409 Conflict` whose headers carry the id to use. In that case we keep the new id and send
+        the request once more.
+    */
+    pub fn request<T>(&self, request: &TransmissionRequest) -> RustorResult<T>
+    where T: DeserializeOwned {
+        self.runtime.block_on(async {
+            let mut response = self.send(request).await?;
+            if response.status() == StatusCode::CONFLICT {
+                let session_id = response
+                    .headers()
+                    .get(SESSION_ID_HEADER)
+                    .and_then(|value| value.to_str().ok())
+                    .unwrap_or_default()
+                    .to_string();
+                *self.session_id.lock().unwrap() = session_id;
+                response = self.send(request).await?;
+            }
+            if response.status() == StatusCode::UNAUTHORIZED {
+                return Err(RustorError::Auth(format!("{} rejected the user / password", self.url)));
+            }
+            match response.error_for_status() {
+                Ok(response) => match response.text().await {
+                    Ok(body) => serde_json::from_str(&body).map_err(|e| RustorError::Parse(e.to_string())),
+                    Err(e) => Err(RustorError::Transport(e.to_string()))
+                },
+                Err(e) => Err(RustorError::Transport(e.to_string()))
+            }
+        })
+    }
+
+    async fn send(&self, request: &TransmissionRequest) -> RustorResult<Response> {
+        let session_id = self.session_id.lock().unwrap().clone();
+        let mut request_builder = self.client
+            .post(&self.url)
+            .header(SESSION_ID_HEADER, session_id)
+            .json(request);
+        if let Some((user, password)) = &self.credentials {
+            request_builder = request_builder.basic_auth(user, Some(password));
+        }
+        request_builder
+            .send()
+            .await
+            .map_err(|e| RustorError::Transport(e.to_string()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+    use crate::client::stub_daemon::serve;
+    use crate::dto::transmission_dto::TransmissionResponse;
+    use crate::service::transmission_service::TransmissionService;
+    use super::*;
+
+    const SUCCESS: &str = r#"{"arguments":{"torrents":[{"id":1,"name":"debian.iso"}]},"result":"success"}"#;
+
+    fn rpc_client(url: String) -> RpcClient {
+        RpcClient::new(Builder::new_current_thread().enable_all().build().unwrap(), url, None)
+    }
+
+    #[test]
+    fn retries_with_the_session_id_of_a_409() {
+        let (url, server) = serve(vec![
+            (409, vec![(SESSION_ID_HEADER, "abc123")], ""),
+            (200, vec![], SUCCESS),
+        ]);
+        let response: TransmissionResponse = rpc_client(url)
+            .request(&TransmissionRequest::new("torrent-get", json!({ "fields": ["id", "name"] })))
+            .unwrap();
+        assert_eq!(response.result, "success");
+        assert_eq!(response.arguments.torrents[0].name, "debian.iso");
+        let requests = server.join().unwrap();
+        assert_eq!(requests.len(), 2);
+        assert!(requests[1].head.contains("x-transmission-session-id: abc123"));
+    }
+
+    #[test]
+    fn keeps_the_session_id_for_the_next_requests() {
+        let (url, server) = serve(vec![
+            (409, vec![(SESSION_ID_HEADER, "abc123")], ""),
+            (200, vec![], SUCCESS),
+            (200, vec![], SUCCESS),
+        ]);
+        let rpc_client = rpc_client(url);
+        let request = TransmissionRequest::new("torrent-get", json!({}));
+        rpc_client.request::<TransmissionResponse>(&request).unwrap();
+        rpc_client.request::<TransmissionResponse>(&request).unwrap();
+        assert!(server.join().unwrap()[2].head.contains("x-transmission-session-id: abc123"));
+    }
+
+    #[test]
+    fn maps_401_to_an_auth_error() {
+        let (url, server) = serve(vec![(401, vec![], "Unauthorized")]);
+        let result = rpc_client(url).request::<TransmissionResponse>(&TransmissionRequest::new("torrent-get", json!({})));
+        assert!(matches!(result, Err(RustorError::Auth(_))));
+        server.join().unwrap();
+    }
+
+    #[test]
+    fn maps_other_http_errors_to_a_transport_error() {
+        let (url, server) = serve(vec![(500, vec![], "")]);
+        let result = rpc_client(url).request::<TransmissionResponse>(&TransmissionRequest::new("torrent-get", json!({})));
+        assert!(matches!(result, Err(RustorError::Transport(_))));
+        server.join().unwrap();
+    }
+
+    #[test]
+    fn maps_a_malformed_body_to_a_parse_error() {
+        let (url, server) = serve(vec![(200, vec![], "not json")]);
+        let result = rpc_client(url).request::<TransmissionResponse>(&TransmissionRequest::new("torrent-get", json!({})));
+        assert!(matches!(result, Err(RustorError::Parse(_))));
+        server.join().unwrap();
+    }
+
+    #[test]
+    fn is_local_only_for_loopback_hosts() {
+        let is_local = |url: &str| rpc_client(url.to_string()).is_local();
+        assert!(is_local("http://localhost:9091/transmission/rpc"));
+        assert!(is_local("http://127.0.0.1:9091/transmission/rpc"));
+        assert!(is_local("https://[::1]:9091/transmission/rpc"));
+        assert!(!is_local("http://nas.lan:9091/transmission/rpc"));
+        assert!(!is_local("http://192.168.1.10:9091/transmission/rpc"));
+    }
+
+    #[test]
+    fn maps_a_failed_result_to_an_rpc_error() {
+        let (url, server) = serve(vec![(200, vec![], r#"{"arguments":{},"result":"no such torrent"}"#)]);
+        let result = TransmissionService::new(rpc_client(url)).torrent_start(&[42]);
+        assert_eq!(result.err(), Some(RustorError::Rpc("no such torrent".to_string())));
+        server.join().unwrap();
+    }
+}
@@ -0,0 +1,56 @@
+use std::io::{BufRead, BufReader, Read, Write};
+use std::net::TcpListener;
+use std::thread;
+use std::thread::JoinHandle;
+use serde_json::Value;
+
+// status, headers and body
+pub type StubResponse = (u16, Vec<(&'static str, &'static str)>, &'static str);
+
+// A request as the stub got it, header names and values lower-cased
+pub struct StubRequest {
+    pub head: String,
+    pub body: String
+}
+
+impl StubRequest {
+
+    pub fn json(&self) -> Value {
+        serde_json::from_str(&self.body).unwrap_or_default()
+    }
+}
+
+/*
+    Fake daemon answering one request per connection with the given responses, in order.
+    Returns its url and, once every response was sent, the requests it got.
+*/
+pub fn serve(responses: Vec<StubResponse>) -> (String, JoinHandle<Vec<StubRequest>>) {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let url = format!("http://{}/transmission/rpc", listener.local_addr().unwrap());
+    let handle = thread::spawn(move || {
+        responses.into_iter().map(|(status, headers, body)| {
+            let (mut stream, _) = listener.accept().unwrap();
+            let mut reader = BufReader::new(stream.try_clone().unwrap());
+            let mut head = String::new();
+            loop {
+                let mut line = String::new();
+                reader.read_line(&mut line).unwrap();
+                if line == "\r\n" || line.is_empty() {
+                    break;
+                }
+                head.push_str(&line.to_lowercase());
+            }
+            let length = head
+                .lines()
+                .find_map(|line| line.strip_prefix("content-length:"))
+                .and_then(|length| length.trim().parse::<usize>().ok())
+                .unwrap_or_default();
+            let mut request_body = vec![0; length];
+            reader.read_exact(&mut request_body).unwrap();
+            let headers: String = headers.iter().map(|(name, value)| format!("{name}: {value}\r\n")).collect();
+            write!(stream, "HTTP/1.1 {status} Stub\r\n{headers}Content-Length: {}\r\nConnection: close\r\n\r\n{body}", body.len()).unwrap();
+            StubRequest { head, body: String::from_utf8_lossy(&request_body).to_string() }
+        }).collect()
+    });
+    (url, handle)
+}
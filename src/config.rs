@@ -1,25 +1,113 @@
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 use std::{env, fs};
 use std::io::Error;
 use std::iter::Map;
+use std::path::Path;
+use std::time::Duration;
 use clap::Parser;
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
+use crate::error::{RustorError, RustorResult};
 
 #[derive(Default, Parser)]
 #[command(name = "Rustor")]
 pub struct Params {
     #[arg(long, required = false, help = "Location of configuration file.")]
     config_file: Option<String>,
+    #[arg(long, required = false, help = "Name of the `[profiles.<name>]` daemon to connect to.")]
+    profile: Option<String>,
+    #[arg(long, required = false, help = "Milliseconds between two refreshes of the torrent list (overrides config file).")]
+    refresh_interval_ms: Option<u64>,
 }
 
+// Name under which the `[connection]` table is listed among the profiles
+pub const DEFAULT_PROFILE: &str = "default";
+
+// Milliseconds between two polls of the daemon when neither the config file nor the cli say otherwise
+pub const DEFAULT_REFRESH_INTERVAL_MS: u64 = 3000;
+
 #[derive(Default, Clone, Deserialize)]
 pub struct ConfigValues {
-    key_bindings: HashMap<ConfigKeyBindingKey, char>
+    key_bindings: HashMap<ConfigKeyBindingKey, char>,
+    #[serde(default)]
+    connection: ConnectionValues,
+    #[serde(default)]
+    profiles: BTreeMap<String, ConnectionValues>,
+    refresh_interval_ms: Option<u64>,
+    #[serde(default)]
+    sort: SortValues,
+    // latest first, see `Config::save_recent_dirs`
+    #[serde(default)]
+    recent_dirs: Vec<String>
+}
+
+// Columns of the Home torrent table which can be sorted
+#[derive(Clone, Copy, Default, PartialEq, Deserialize, Serialize)]
+pub enum SortColumn {
+    #[default]
+    Id,
+    Name,
+    Eta,
+    Done,
+    Download,
+    Upload,
+    Size,
+    Downloaded,
+    AddedOn
+}
+
+// Sort order of the Home torrent table, saved back to the config file whenever it changes
+#[derive(Clone, Copy, Default, PartialEq, Deserialize, Serialize)]
+#[serde(default)] // automatically use a default value when none is present in the data
+pub struct SortValues {
+    pub column: SortColumn,
+    pub descending: bool
+}
+
+#[derive(Clone, Deserialize)]
+#[serde(default)] // automatically use a default value when none is present in the data
+pub struct ConnectionValues {
+    host: String,
+    port: u16,
+    rpc_path: String,
+    user: Option<String>,
+    password: Option<String>,
+    https: bool
+}
+
+impl Default for ConnectionValues {
+    fn default() -> Self {
+        Self {
+            host: "localhost".to_string(),
+            port: 9091,
+            rpc_path: "/transmission/rpc".to_string(),
+            user: None,
+            password: None,
+            https: false
+        }
+    }
+}
+
+impl ConnectionValues {
+
+    pub fn url(&self) -> String {
+        let scheme = if self.https { "https" } else { "http" };
+        format!("{}://{}:{}{}", scheme, self.host, self.port, self.rpc_path)
+    }
+
+    // HTTP basic-auth credentials, only when a user has been configured
+    pub fn credentials(&self) -> Option<(String, String)> {
+        self.user
+            .clone()
+            .map(|user| (user, self.password.clone().unwrap_or_default()))
+    }
 }
 
 #[derive(Clone)]
 pub struct Config {
-    values: ConfigValues
+    config_file: String,
+    values: ConfigValues,
+    profile: String,
+    refresh_interval_ms: u64
 }
 
 impl ConfigValues {
@@ -27,6 +115,20 @@ impl ConfigValues {
     pub fn key_bindings(&self) -> &HashMap<ConfigKeyBindingKey, char> {
         &self.key_bindings
     }
+
+    // All daemons we can connect to, the `[connection]` table first followed by the `[profiles.<name>]` sections
+    pub fn profiles(&self) -> Vec<(String, ConnectionValues)> {
+        let mut profiles = vec![(DEFAULT_PROFILE.to_string(), self.connection.clone())];
+        profiles.extend(self.profiles.iter().map(|(name, connection)| (name.clone(), connection.clone())));
+        profiles
+    }
+
+    pub fn profile_connection(&self, profile: &str) -> Option<ConnectionValues> {
+        self.profiles()
+            .into_iter()
+            .find(|(name, _)| name == profile)
+            .map(|(_, connection)| connection)
+    }
 }
 
 #[derive(Hash, Eq, PartialEq, Clone, Deserialize)]
@@ -34,62 +136,68 @@ pub enum ConfigKeyBindingKey {
     KbAdd,
     KbDel,
     KbDownload,
+    KbGroups,
     KbInfo,
     KbHelp,
     KbHome,
+    KbLimits,
+    KbMove,
     KbOpen,
+    KbProfiles,
     KbQuit,
     KbReAnn,
-    KbSearch
+    KbSearch,
+    KbSettings,
+    KbStats
 }
 
 impl Config {
 
-    pub fn new(params: Params) -> Self {
+    pub fn new(params: Params) -> RustorResult<Self> {
 
         // Read_config
         let config_file = match params.config_file {
             Some(file) => file,
             None => {
-                let home_dir = env::home_dir()
-                    .expect("Error: Tried to search for `$HOME/.rustor/config.toml` but user's Home directory was not found!")
-                    .to_str()
-                    .unwrap()
-                    .to_string();
-                let file = format!("{}/.rustor/config.toml", home_dir);
-                match fs::exists(file.clone()) {
-                    Ok(true) => file,
-                    Ok(false) => "".to_string(),
-                    Err(_) => "".to_string(),
-                }
+                let home_dir = env::home_dir().ok_or_else(|| RustorError::Config(
+                    "Tried to search for `$HOME/.rustor/config.toml` but user's Home directory was not found!".to_string()
+                ))?;
+                format!("{}/.rustor/config.toml", home_dir.to_string_lossy())
             }
         };
-        let mut values: ConfigValues = if !config_file.is_empty() {
+        // The default file is not required, it only gets created when something has to be saved
+        let mut values: ConfigValues = if fs::exists(config_file.clone()).unwrap_or(false) {
                 match fs::read_to_string(config_file.clone()) {
                     Ok(content) => {
                         match toml::from_str(&content) {
                             Ok(vals) => Ok(vals),
-                            Err(e) => Err(Error::other(format!("Failed to parse config file: {}", e)))
+                            Err(e) => Err(RustorError::Config(format!("Failed to parse config file: {}", e)))
                         }
                     },
-                    Err(_) => { Err(Error::other(format!("Failed to read config file: {}", config_file.clone()))) }
+                    Err(_) => { Err(RustorError::Config(format!("Failed to read config file: {}", config_file.clone()))) }
                 }
             } else {
                 Ok(ConfigValues::default())
-            }.unwrap();
+            }?;
 
         // Add key bindings missing from config file
         let mut default_key_bindings = HashMap::new();
         default_key_bindings.insert(ConfigKeyBindingKey::KbAdd, 'a');
         default_key_bindings.insert(ConfigKeyBindingKey::KbDel, 'd');
         default_key_bindings.insert(ConfigKeyBindingKey::KbDownload, 'd');
+        default_key_bindings.insert(ConfigKeyBindingKey::KbGroups, 'g');
         default_key_bindings.insert(ConfigKeyBindingKey::KbInfo, 'i');
         default_key_bindings.insert(ConfigKeyBindingKey::KbHelp, 'h');
         default_key_bindings.insert(ConfigKeyBindingKey::KbHome, 'b');
+        default_key_bindings.insert(ConfigKeyBindingKey::KbLimits, 'l');
+        default_key_bindings.insert(ConfigKeyBindingKey::KbMove, 'v');
         default_key_bindings.insert(ConfigKeyBindingKey::KbOpen, 'o');
+        default_key_bindings.insert(ConfigKeyBindingKey::KbProfiles, 'p');
         default_key_bindings.insert(ConfigKeyBindingKey::KbQuit, 'q');
         default_key_bindings.insert(ConfigKeyBindingKey::KbReAnn, 'r');
         default_key_bindings.insert(ConfigKeyBindingKey::KbSearch, 's');
+        default_key_bindings.insert(ConfigKeyBindingKey::KbSettings, 'e');
+        default_key_bindings.insert(ConfigKeyBindingKey::KbStats, 't');
         let mut missing_key_bindings: HashMap<ConfigKeyBindingKey, char> = HashMap::new();
         default_key_bindings.iter().for_each(|(k, v)| {
             match values.key_bindings.iter().find(| (key, value) | **key == *k) {
@@ -99,10 +207,110 @@ impl Config {
          });
         values.key_bindings.extend(missing_key_bindings.into_iter().map(|(k, v)| (k.clone(), v.clone())));
 
-        Self { values }
+        // Profile to connect to on startup
+        let profile = params.profile.unwrap_or(DEFAULT_PROFILE.to_string());
+        if values.profile_connection(&profile).is_none() {
+            return Err(RustorError::Config(format!("Profile `{}` was not found in config file!", profile)));
+        }
+
+        // Cli argument wins over config file
+        let refresh_interval_ms = params.refresh_interval_ms
+            .or(values.refresh_interval_ms)
+            .unwrap_or(DEFAULT_REFRESH_INTERVAL_MS);
+
+        Ok(Self { config_file, values, profile, refresh_interval_ms })
     }
 
     pub fn values(&self) -> ConfigValues {
         self.values.clone()
     }
+
+    pub fn profile(&self) -> &str {
+        &self.profile
+    }
+
+    pub fn connection(&self) -> ConnectionValues {
+        self.values.profile_connection(&self.profile).unwrap_or_default()
+    }
+
+    pub fn refresh_interval(&self) -> Duration {
+        Duration::from_millis(self.refresh_interval_ms)
+    }
+
+    pub fn sort(&self) -> SortValues {
+        self.values.sort
+    }
+
+    pub fn save_sort(&mut self, sort: SortValues) -> Result<(), Error> {
+        self.values.sort = sort;
+        self.save_value("sort", toml::Value::try_from(sort).map_err(Error::other)?)
+    }
+
+    // Destinations torrents were lately moved to, latest first
+    pub fn recent_dirs(&self) -> Vec<String> {
+        self.values.recent_dirs.clone()
+    }
+
+    pub fn save_recent_dirs(&mut self, recent_dirs: Vec<String>) -> Result<(), Error> {
+        self.values.recent_dirs = recent_dirs;
+        self.save_value("recent_dirs", toml::Value::try_from(&self.values.recent_dirs).map_err(Error::other)?)
+    }
+
+    /*
+        Only the given top level key of the file is rewritten, everything else is kept as is (except
+        for comments and formatting which do not survive the round trip).
+    */
+    fn save_value(&self, key: &str, value: toml::Value) -> Result<(), Error> {
+        let mut table: toml::Table = match fs::read_to_string(&self.config_file) {
+            Ok(content) => toml::from_str(&content).map_err(|e| Error::other(format!("Failed to parse config file: {}", e)))?,
+            Err(_) => toml::Table::new()
+        };
+        table.entry("key_bindings").or_insert(toml::Value::Table(toml::Table::new()));
+        table.insert(key.to_string(), value);
+        if let Some(dir) = Path::new(&self.config_file).parent() {
+            fs::create_dir_all(dir)?;
+        }
+        fs::write(&self.config_file, toml::to_string(&table).map_err(Error::other)?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Config file with the given content, unique per test since they run in parallel
+    fn config_file(name: &str, content: &str) -> String {
+        let file = env::temp_dir().join(format!("rustor-config-{}-{}.toml", name, std::process::id()));
+        fs::write(&file, content).unwrap();
+        file.to_string_lossy().to_string()
+    }
+
+    fn params(config_file: String, profile: Option<&str>) -> Params {
+        Params { config_file: Some(config_file), profile: profile.map(str::to_string), ..Default::default() }
+    }
+
+    #[test]
+    fn missing_file_gives_the_defaults() {
+        let file = env::temp_dir().join("rustor-config-does-not-exist.toml").to_string_lossy().to_string();
+        let config = Config::new(params(file, None)).unwrap();
+        assert_eq!(config.profile(), DEFAULT_PROFILE);
+        assert_eq!(config.connection().url(), "http://localhost:9091/transmission/rpc");
+    }
+
+    #[test]
+    fn unknown_profile_is_an_error() {
+        let file = config_file("profile", "[key_bindings]\n[profiles.nas]\nhost = \"nas\"\n");
+        assert!(Config::new(params(file.clone(), Some("nas"))).is_ok());
+        let result = Config::new(params(file.clone(), Some("seedbox")));
+        let _ = fs::remove_file(file);
+        assert!(matches!(result, Err(RustorError::Config(msg)) if msg.contains("seedbox")));
+    }
+
+    #[test]
+    fn invalid_file_is_an_error() {
+        let file = config_file("invalid", "[key_bindings\n");
+        let result = Config::new(params(file.clone(), None));
+        let _ = fs::remove_file(file);
+        assert!(matches!(result, Err(RustorError::Config(msg)) if msg.starts_with("Failed to parse config file")));
+    }
 }
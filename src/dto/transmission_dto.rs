@@ -1,4 +1,7 @@
-use serde::{Deserialize};
+use serde::{Deserialize, Deserializer, Serialize};
+use serde_json::{Map, Value};
+use base64::Engine;
+use base64::engine::general_purpose::STANDARD;
 use crate::util::Util;
 
 #[derive(Default, Clone,  Deserialize)]
@@ -67,6 +70,8 @@ pub struct TransmissionTorrent {
     pub piece_count: i64,
     #[serde(rename = "pieceSize")]
     pub piece_size: i64,
+    // base64 bitfield, one bit per piece (most significant bit first) set once the piece is complete
+    pub pieces: String,
     #[serde(rename = "rateDownload")]
     pub rate_download: i64,
     #[serde(rename = "rateUpload")]
@@ -96,6 +101,10 @@ pub struct TransmissionTorrent {
     pub total_size: i64,
     #[serde(rename = "trackerStats")]
     pub tracker_stats: Vec<TransmissionTorrentTrackerStat>,
+    pub priorities: Vec<i64>,
+    #[serde(deserialize_with = "bools_or_ints")]
+    pub wanted: Vec<bool>,
+    pub trackers: Vec<TransmissionTorrentTracker>,
     #[serde(rename = "uploadLimit")]
     pub upload_limit: i64,
     #[serde(rename = "uploadLimited")]
@@ -181,6 +190,53 @@ impl TransmissionTorrent {
         res
     }
 
+    // Decoded `pieces`, whether every piece is complete
+    pub fn completed_pieces(&self) -> Vec<bool> {
+        let bitfield = STANDARD.decode(&self.pieces).unwrap_or_default();
+        (0..self.piece_count.max(0) as usize)
+            .map(|piece| bitfield.get(piece / 8).is_some_and(|byte| byte & (0x80 >> (piece % 8)) != 0))
+            .collect()
+    }
+
+    // Host part of every announce url (e.g. "tracker.example.org")
+    pub fn tracker_hosts(&self) -> Vec<String> {
+        self.trackers
+            .iter()
+            .map(|tracker| {
+                let url = tracker.announce.split("://").last().unwrap_or_default();
+                let host = url.split(['/', ':']).next().unwrap_or_default();
+                host.to_string()
+            })
+            .collect()
+    }
+
+    // Waiting for or running a `torrent-verify`
+    pub fn is_checking(&self) -> bool {
+        self.status == 1 || self.status == 2
+    }
+
+    /*
+       Apply a successful `torrent-rename-path` to what we already know
+       @path: the torrent name or the path of a file / directory inside it
+       @name: new last component of `path`
+    */
+    pub fn rename_path(&mut self, path: &str, name: &str) {
+        let renamed = match path.rsplit_once('/') {
+            Some((parent, _)) => format!("{}/{}", parent, name),
+            None => name.to_string()
+        };
+        if self.name == path {
+            self.name = name.to_string();
+        }
+        self.files.iter_mut().for_each(|file| {
+            if file.name == path {
+                file.name = renamed.clone();
+            } else if let Some(rest) = file.name.strip_prefix(&format!("{}/", path)) {
+                file.name = format!("{}/{}", renamed, rest);
+            }
+        });
+    }
+
     fn calc_percentage_done(&self) -> f64 {
         if self.left_until_done == 0 {
             return 100f64;
@@ -226,6 +282,41 @@ pub struct TransmissionTorrentPeer {
     pub rate_to_peer: i64,
 }
 
+impl TransmissionTorrentPeer {
+
+    // Meaning of every letter of `flag_str`, see https://github.com/transmission/transmission/blob/main/docs/peer-status-text.md
+    pub fn flag_meanings(&self) -> Vec<&'static str> {
+        self.flag_str
+            .chars()
+            .filter_map(|flag| match flag {
+                'O' => Some("optimistic unchoke"),
+                'D' => Some("downloading from peer"),
+                'd' => Some("would download from peer if it let us"),
+                'U' => Some("uploading to peer"),
+                'u' => Some("would upload to peer if it asked"),
+                'K' => Some("peer unchoked us, but we are not interested"),
+                '?' => Some("we unchoked peer, but it is not interested"),
+                'E' => Some("encrypted"),
+                'H' => Some("discovered through DHT"),
+                'X' => Some("discovered through PEX"),
+                'I' => Some("incoming connection"),
+                'T' => Some("connected through uTP"),
+                _ => None
+            })
+            .collect()
+    }
+}
+
+#[derive(Default, Clone, Deserialize)]
+#[serde(default)] // automatically use a default value when none is present in the data
+pub struct TransmissionTorrentTracker {
+    pub announce: String,
+    pub id: i64,
+    pub scrape: String,
+    pub sitename: String,
+    pub tier: i64,
+}
+
 #[derive(Default, Clone, Deserialize)]
 #[serde(default)] // automatically use a default value when none is present in the data
 pub struct TransmissionTorrentTrackerStat {
@@ -282,27 +373,62 @@ pub struct TransmissionTorrentTrackerStat {
 #[derive(Default, Clone, Deserialize)]
 #[serde(default)] // automatically use a default value when none is present in the data
 pub struct TransmissionTorrentFile {
+    #[serde(rename = "beginPiece")]
     pub begin_piece: i64,
     #[serde(rename = "bytesCompleted")]
     pub bytes_completed: i64,
+    #[serde(rename = "endPiece")]
     pub end_piece: i64,
     pub length: i64,
     pub name: String,
 }
 
-#[derive(Default, Deserialize)]
+// File priorities as used by `priorities` and `torrent-set`
+pub const PRIORITY_LOW: i64 = -1;
+pub const PRIORITY_NORMAL: i64 = 0;
+pub const PRIORITY_HIGH: i64 = 1;
+
+#[derive(Default, Clone, Deserialize)]
 #[serde(default)] // automatically use a default value when none is present in the data
 pub struct TransmissionTorrentFiles {
     pub files: Vec<TransmissionTorrentFile>,
     pub name: String,
     pub priorities: Vec<i64>,
-    pub wanted: Vec<i64>,
+    #[serde(deserialize_with = "bools_or_ints")]
+    pub wanted: Vec<bool>,
+}
+
+impl From<&TransmissionTorrent> for TransmissionTorrentFiles {
+    fn from(torrent: &TransmissionTorrent) -> Self {
+        Self {
+            files: torrent.files.clone(),
+            name: torrent.name.clone(),
+            priorities: torrent.priorities.clone(),
+            wanted: torrent.wanted.clone()
+        }
+    }
+}
+
+// Older daemons send `wanted` as 0/1, newer ones as booleans
+fn bools_or_ints<'de, D>(deserializer: D) -> Result<Vec<bool>, D::Error>
+where D: Deserializer<'de> {
+    let values: Vec<Value> = Vec::deserialize(deserializer)?;
+    Ok(values
+        .iter()
+        .map(|value| value.as_bool().unwrap_or_else(|| value.as_i64().unwrap_or(0) != 0))
+        .collect())
 }
 
 #[derive(Default, Deserialize)]
 #[serde(default)] // automatically use a default value when none is present in the data
 pub struct TransmissionResponseArguments {
     pub torrents: Vec<TransmissionTorrent>,
+    #[serde(rename = "torrent-added")]
+    pub torrent_added: Option<TransmissionTorrent>,
+    #[serde(rename = "torrent-duplicate")]
+    pub torrent_duplicate: Option<TransmissionTorrent>,
+    // ids of the torrents removed lately, only sent for `"ids": "recently-active"`
+    pub removed: Vec<i64>,
 }
 
 #[derive(Default, Deserialize)]
@@ -312,3 +438,168 @@ pub struct TransmissionResponse {
     pub result: String,
     pub tag: i64,
 }
+
+// Response of the methods with arguments of their own (`session-get`, `session-stats`, `free-space`, raw `torrent-get`)
+#[derive(Default, Deserialize)]
+#[serde(default)] // automatically use a default value when none is present in the data
+pub struct TransmissionSessionResponse<T> {
+    pub arguments: T,
+    pub result: String,
+}
+
+#[derive(Default, Clone, Deserialize)]
+#[serde(default)] // automatically use a default value when none is present in the data
+pub struct TransmissionSession {
+    #[serde(rename = "download-dir")]
+    pub download_dir: String,
+    #[serde(rename = "rpc-version")]
+    pub rpc_version: i64,
+    pub version: String,
+}
+
+#[derive(Default, Clone, Deserialize)]
+#[serde(default)] // automatically use a default value when none is present in the data
+pub struct TransmissionSessionStats {
+    #[serde(rename = "activeTorrentCount")]
+    pub active_torrent_count: i64,
+    #[serde(rename = "downloadSpeed")]
+    pub download_speed: i64,
+    #[serde(rename = "pausedTorrentCount")]
+    pub paused_torrent_count: i64,
+    #[serde(rename = "torrentCount")]
+    pub torrent_count: i64,
+    #[serde(rename = "uploadSpeed")]
+    pub upload_speed: i64,
+    #[serde(rename = "cumulative-stats")]
+    pub cumulative_stats: TransmissionStats,
+    #[serde(rename = "current-stats")]
+    pub current_stats: TransmissionStats,
+}
+
+#[derive(Default, Clone, Deserialize)]
+#[serde(default)] // automatically use a default value when none is present in the data
+pub struct TransmissionStats {
+    #[serde(rename = "uploadedBytes")]
+    pub uploaded_bytes: i64,
+    #[serde(rename = "downloadedBytes")]
+    pub downloaded_bytes: i64,
+    #[serde(rename = "filesAdded")]
+    pub files_added: i64,
+    #[serde(rename = "sessionCount")]
+    pub session_count: i64,
+    #[serde(rename = "secondsActive")]
+    pub seconds_active: i64,
+}
+
+impl TransmissionStats {
+
+    // Uploaded per downloaded byte, `None` before anything was downloaded
+    pub fn ratio(&self) -> Option<f64> {
+        (self.downloaded_bytes > 0).then(|| self.uploaded_bytes as f64 / self.downloaded_bytes as f64)
+    }
+}
+
+#[derive(Default, Clone, Deserialize)]
+#[serde(default)] // automatically use a default value when none is present in the data
+pub struct TransmissionFreeSpace {
+    pub path: String,
+    #[serde(rename = "size-bytes")]
+    pub size_bytes: i64,
+    // only sent by newer daemons (rpc-version 17)
+    pub total_size: Option<i64>,
+}
+
+// `torrent-get` arguments kept as sent, every torrent keyed by the rpc names of the requested fields
+#[derive(Default, Clone, Deserialize)]
+#[serde(default)] // automatically use a default value when none is present in the data
+pub struct TransmissionTorrentValues {
+    pub torrents: Vec<Map<String, Value>>,
+}
+
+// Bandwidth group as sent by `group-get` (rpc-version 17), limits are in kB/s
+#[derive(Default, Clone, Deserialize)]
+#[serde(default)] // automatically use a default value when none is present in the data
+pub struct TransmissionGroup {
+    pub name: String,
+    #[serde(rename = "honorsSessionLimits")]
+    pub honors_session_limits: bool,
+    #[serde(rename = "speed-limit-down-enabled")]
+    pub speed_limit_down_enabled: bool,
+    #[serde(rename = "speed-limit-down")]
+    pub speed_limit_down: i64,
+    #[serde(rename = "speed-limit-up-enabled")]
+    pub speed_limit_up_enabled: bool,
+    #[serde(rename = "speed-limit-up")]
+    pub speed_limit_up: i64,
+}
+
+#[derive(Default, Clone, Deserialize)]
+#[serde(default)] // automatically use a default value when none is present in the data
+pub struct TransmissionGroups {
+    pub group: Vec<TransmissionGroup>,
+}
+
+#[derive(Serialize)]
+pub struct TransmissionRequest {
+    pub method: String,
+    pub arguments: Value,
+}
+
+impl TransmissionRequest {
+
+    pub fn new(method: &str, arguments: Value) -> Self {
+        Self { method: method.to_string(), arguments }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn torrent(bitfield: &[u8], piece_count: i64) -> TransmissionTorrent {
+        TransmissionTorrent { pieces: STANDARD.encode(bitfield), piece_count, ..Default::default() }
+    }
+
+    #[test]
+    fn completed_pieces_reads_the_most_significant_bit_first() {
+        assert_eq!(torrent(&[0b1010_0001], 8).completed_pieces(), vec![true, false, true, false, false, false, false, true]);
+    }
+
+    #[test]
+    fn completed_pieces_ignores_the_padding_of_a_trailing_partial_byte() {
+        let pieces = torrent(&[0xFF, 0b0111_1111], 10).completed_pieces();
+        assert_eq!(pieces.len(), 10);
+        assert!(pieces[..8].iter().all(|piece| *piece));
+        assert_eq!(pieces[8..], [false, true]);
+    }
+
+    #[test]
+    fn completed_pieces_missing_from_a_short_bitfield_are_not_complete() {
+        assert_eq!(torrent(&[0xFF], 10).completed_pieces()[8..], [false, false]);
+    }
+
+    #[test]
+    fn completed_pieces_of_invalid_base64_are_not_complete() {
+        let torrent = TransmissionTorrent { pieces: "not base64!".to_string(), piece_count: 3, ..Default::default() };
+        assert_eq!(torrent.completed_pieces(), vec![false, false, false]);
+    }
+
+    #[test]
+    fn completed_pieces_without_pieces() {
+        assert!(torrent(&[0xFF], 0).completed_pieces().is_empty());
+        assert!(torrent(&[0xFF], -1).completed_pieces().is_empty());
+    }
+
+    #[test]
+    fn flag_meanings_follow_the_flags_and_skip_unknown_ones() {
+        let peer = TransmissionTorrentPeer { flag_str: "DEz?".to_string(), ..Default::default() };
+        assert_eq!(peer.flag_meanings(), vec!["downloading from peer", "encrypted", "we unchoked peer, but it is not interested"]);
+    }
+
+    #[test]
+    fn flag_meanings_tell_the_case_of_the_letters_apart() {
+        let peer = TransmissionTorrentPeer { flag_str: "Uu".to_string(), ..Default::default() };
+        assert_eq!(peer.flag_meanings(), vec!["uploading to peer", "would upload to peer if it asked"]);
+        assert!(TransmissionTorrentPeer::default().flag_meanings().is_empty());
+    }
+}
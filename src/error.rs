@@ -0,0 +1,37 @@
+use std::fmt;
+
+pub type RustorResult<T> = Result<T, RustorError>;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum RustorError {
+    // The daemon (or any other http endpoint) could not be reached or answered with an http error
+    Transport(String),
+    // The daemon rejected the credentials (http 401)
+    Auth(String),
+    // The daemon was reached but answered with a `result` other than "success"
+    Rpc(String),
+    // A response (or a value inside it) could not be parsed
+    Parse(String),
+    // A torrent search provider failed
+    SearchProvider(String),
+    // A local command could not be executed
+    Command(String),
+    // The config file (or a cli argument) is invalid
+    Config(String)
+}
+
+impl fmt::Display for RustorError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            RustorError::Transport(msg) => write!(f, "Connection error: {msg}"),
+            RustorError::Auth(msg) => write!(f, "Authentication error: {msg}"),
+            RustorError::Rpc(msg) => write!(f, "Daemon error: {msg}"),
+            RustorError::Parse(msg) => write!(f, "Parse error: {msg}"),
+            RustorError::SearchProvider(msg) => write!(f, "Search error: {msg}"),
+            RustorError::Command(msg) => write!(f, "Command error: {msg}"),
+            RustorError::Config(msg) => write!(f, "Config error: {msg}"),
+        }
+    }
+}
+
+impl std::error::Error for RustorError {}
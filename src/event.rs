@@ -0,0 +1,64 @@
+use std::io;
+use std::sync::mpsc::{channel, Receiver, Sender};
+use std::thread;
+use std::time::Duration;
+use crossterm::event::{self, Event};
+use crate::error::RustorResult;
+use crate::poller::DaemonSnapshot;
+
+pub enum AppEvent {
+    // Anything crossterm reports (keys, resize, focus, ...)
+    Input(Event),
+    // Periodic wake up so time based state (e.g. expiring toasts) gets redrawn
+    Tick,
+    // Fresh daemon state published by the `Poller`
+    Snapshot(Box<DaemonSnapshot>),
+    // Outcome of probing the daemon of a profile, see `ProfilesScreen::refresh`
+    ProfileProbe { profile: String, result: RustorResult<()> }
+}
+
+/*
+    Single queue the main loop waits on, fed by an input reader thread, a tick thread and
+    whoever else holds a `sender()` (e.g. the `Poller` or the profile probes).
+*/
+pub struct EventLoop {
+    sender: Sender<AppEvent>,
+    receiver: Receiver<AppEvent>
+}
+
+impl EventLoop {
+
+    pub fn new(tick_rate: Duration) -> Self {
+        let (sender, receiver) = channel();
+
+        let input_sender = sender.clone();
+        thread::spawn(move || {
+            while let Ok(event) = event::read() {
+                if input_sender.send(AppEvent::Input(event)).is_err() {
+                    break;
+                }
+            }
+        });
+
+        let tick_sender = sender.clone();
+        thread::spawn(move || {
+            loop {
+                thread::sleep(tick_rate);
+                if tick_sender.send(AppEvent::Tick).is_err() {
+                    break;
+                }
+            }
+        });
+
+        Self { sender, receiver }
+    }
+
+    pub fn sender(&self) -> Sender<AppEvent> {
+        self.sender.clone()
+    }
+
+    // Blocks until the next event arrives
+    pub fn next(&self) -> io::Result<AppEvent> {
+        self.receiver.recv().map_err(io::Error::other)
+    }
+}
@@ -3,22 +3,38 @@
 mod app;
 mod config;
 mod dto;
+mod error;
+mod event;
 mod service;
 mod screen;
 mod util;
 mod client;
 mod mapper;
+mod poller;
+mod rate_history;
+mod store;
 
 use crate::app::App;
 use crate::config::{Config, Params};
-use std::sync::{Arc, Mutex};
 use std::io;
 use clap::Parser;
+use crossterm::event::{DisableFocusChange, EnableFocusChange};
+use crossterm::execute;
 
 fn main() -> io::Result<()> {
+    // Reported before the terminal is taken over, so that the message stays readable
+    let config = match Config::new(Params::parse()) {
+        Ok(config) => config,
+        Err(e) => {
+            eprintln!("{e}");
+            std::process::exit(1);
+        }
+    };
     let terminal = ratatui::init();
-    let config = Config::new(Params::parse());
-    let result = App::new(config, Arc::new(Mutex::new(terminal))).run();
+    // FocusGained / FocusLost events let the poller slow down while the terminal is in the background
+    execute!(io::stdout(), EnableFocusChange)?;
+    let result = App::new(config, terminal).run();
+    let _ = execute!(io::stdout(), DisableFocusChange);
     ratatui::restore();
     result
 }
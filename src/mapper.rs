@@ -1,28 +1,29 @@
-use std::io::Error;
+use std::str::FromStr;
 use crate::dto::torrent_dto::{PirateBayInfoTorrent, PirateBayListTorrent, PirateBayTorrentFile, SearchTorrent, SearchTorrentFile, TorrentSource, TorrentsCsvTorrent};
+use crate::error::{RustorError, RustorResult};
 
 pub struct Mapper {}
 
 impl Mapper {
 
-    pub fn pirate_bay_list_torrent_to_search_torrent(source: &PirateBayListTorrent) -> SearchTorrent {
+    pub fn pirate_bay_list_torrent_to_search_torrent(source: &PirateBayListTorrent) -> RustorResult<SearchTorrent> {
         let mut torrent = SearchTorrent::default();
         torrent.id = source.id.clone();
         torrent.name = source.name.clone();
-        torrent.seeders = source.seeders.parse().unwrap();
-        torrent.leechers = source.leechers.parse().unwrap();
-        torrent.size = source.size.parse().unwrap();
-        torrent.created_on = source.added.parse().unwrap();
+        torrent.seeders = Self::parse("seeders", &source.seeders)?;
+        torrent.leechers = Self::parse("leechers", &source.leechers)?;
+        torrent.size = Self::parse("size", &source.size)?;
+        torrent.created_on = Self::parse("added", &source.added)?;
         torrent.info_hash = source.info_hash.clone();
         torrent.source = TorrentSource::PirateBay;
 
-        torrent
+        Ok(torrent)
     }
 
     pub fn pirate_bay_torrent_info_and_files_result_to_search_torrent(
-        torrent_info: &Result<PirateBayInfoTorrent, Error>,
-        torrent_files: &Result<Vec<PirateBayTorrentFile>, Error>
-    ) -> SearchTorrent {
+        torrent_info: &RustorResult<PirateBayInfoTorrent>,
+        torrent_files: &RustorResult<Vec<PirateBayTorrentFile>>
+    ) -> RustorResult<SearchTorrent> {
         let mut torrent = SearchTorrent::default();
         match torrent_info {
             Ok(info) => {
@@ -36,7 +37,7 @@ impl Mapper {
                 torrent.description = info.descr.clone();
                 torrent.source = TorrentSource::PirateBay;
             },
-            Err(_) => torrent.is_error = true
+            Err(e) => return Err(e.clone())
         }
         match torrent_files {
             Ok(files) => {
@@ -55,10 +56,10 @@ impl Mapper {
             Err(_) => torrent.files = vec![]
         }
 
-        torrent
+        Ok(torrent)
     }
 
-    pub fn torrents_csv_torrent_to_search_torrent(source: &TorrentsCsvTorrent) -> SearchTorrent {
+    pub fn torrents_csv_torrent_to_search_torrent(source: &TorrentsCsvTorrent) -> RustorResult<SearchTorrent> {
         let mut torrent = SearchTorrent::default();
         torrent.id = source.id.to_string();
         torrent.name = source.name.clone();
@@ -70,6 +71,12 @@ impl Mapper {
         torrent.descr = "".to_string();
         torrent.source = TorrentSource::TorrentsCsv;
 
-        torrent
+        Ok(torrent)
+    }
+
+    fn parse<T: FromStr>(field: &str, value: &str) -> RustorResult<T> {
+        value
+            .parse()
+            .map_err(|_| RustorError::Parse(format!("invalid `{}` value `{}`", field, value)))
     }
 }
\ No newline at end of file
@@ -0,0 +1,203 @@
+use std::sync::Arc;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::mpsc::{channel, RecvTimeoutError, Sender};
+use std::thread;
+use std::time::{Duration, Instant};
+use crate::client::rpc_client::RpcClient;
+use crate::dto::transmission_dto::{TransmissionFreeSpace, TransmissionSessionStats, TransmissionTorrent};
+use crate::error::RustorResult;
+use crate::event::AppEvent;
+use crate::rate_history::RateHistory;
+use crate::service::transmission_service::TransmissionService;
+use crate::store::TorrentStore;
+
+// Bounds of the (user chosen) refresh interval
+const MIN_INTERVAL_MS: u64 = 250;
+const MAX_INTERVAL_MS: u64 = 60_000;
+// An unreachable daemon is polled at most 2^MAX_BACKOFF_STEPS times less often
+const MAX_BACKOFF_STEPS: u32 = 4;
+// Nobody is looking at an unfocused terminal, poll that many times less often
+const UNFOCUSED_FACTOR: u64 = 5;
+
+// Outcome of one poll, the torrent list itself lands in the `TorrentStore`
+pub struct DaemonSnapshot {
+    pub torrents: RustorResult<()>,
+    // Details of the focused torrent (Info screen), `None` when nothing is focused
+    pub focused_torrent: Option<RustorResult<TransmissionTorrent>>,
+    // Daemon wide numbers (Stats screen), `None` when nobody watches them
+    pub session_stats: Option<RustorResult<SessionStats>>
+}
+
+pub struct SessionStats {
+    pub stats: TransmissionSessionStats,
+    // of the default download directory
+    pub free_space: TransmissionFreeSpace
+}
+
+enum PollerCommand {
+    Refresh,
+    Connect(RpcClient),
+    Focus(Option<i64>),
+    WatchSessionStats(bool),
+    TerminalFocus(bool)
+}
+
+/*
+    Handle to a background thread which polls the daemon every `interval` and publishes a
+    `DaemonSnapshot` as an `AppEvent` once the `TorrentStore` is up to date. Screens acting on the daemon ask for an early `refresh`
+    so the user does not wait a whole interval to see the outcome.
+    Switching to another daemon goes through the poller too, between two polls, so a poll of the previous daemon can
+    never land in the `TorrentStore` after it was cleared.
+    The interval grows on its own while the daemon is unreachable or the terminal is unfocused.
+    Every successful poll also adds a sample to the `RateHistory`.
+*/
+#[derive(Clone)]
+pub struct Poller {
+    commands: Sender<PollerCommand>,
+    // interval chosen by the user (config, cli or at runtime), before any backoff
+    interval_ms: Arc<AtomicU64>
+}
+
+impl Poller {
+
+    pub fn spawn(
+        transmission_service: Arc<TransmissionService>,
+        torrent_store: TorrentStore,
+        rate_history: RateHistory,
+        events: Sender<AppEvent>,
+        interval: Duration
+    ) -> Self {
+        let (commands, receiver) = channel();
+        let interval_ms = Arc::new(AtomicU64::new(Self::clamp(interval.as_millis() as u64)));
+        let thread_interval_ms = interval_ms.clone();
+        thread::spawn(move || {
+            let mut focused_torrent_id = None;
+            let mut is_watching_session_stats = false;
+            let mut is_terminal_focused = true;
+            let mut failures = 0;
+            loop {
+                let snapshot = Self::poll(&transmission_service, &torrent_store, focused_torrent_id, is_watching_session_stats);
+                failures = if snapshot.torrents.is_err() { failures + 1 } else { 0 };
+                if snapshot.torrents.is_ok() {
+                    rate_history.record(&torrent_store.torrents());
+                }
+                if events.send(AppEvent::Snapshot(Box::new(snapshot))).is_err() {
+                    break;
+                }
+
+                // wait for the interval to elapse or for a command asking for an earlier poll
+                let mut deadline = Instant::now() + Self::backoff(thread_interval_ms.load(Ordering::Relaxed), failures, is_terminal_focused);
+                loop {
+                    match receiver.recv_timeout(deadline.saturating_duration_since(Instant::now())) {
+                        Ok(PollerCommand::Focus(torrent_id)) => focused_torrent_id = torrent_id,
+                        Ok(PollerCommand::WatchSessionStats(is_watching)) => is_watching_session_stats = is_watching,
+                        Ok(PollerCommand::TerminalFocus(true)) => is_terminal_focused = true,
+                        // losing focus is no reason to poll, only to wait longer
+                        Ok(PollerCommand::TerminalFocus(false)) => {
+                            is_terminal_focused = false;
+                            deadline = Instant::now() + Self::backoff(thread_interval_ms.load(Ordering::Relaxed), failures, false);
+                            continue;
+                        }
+                        Ok(PollerCommand::Connect(rpc_client)) => {
+                            transmission_service.connect(rpc_client);
+                            torrent_store.clear();
+                            rate_history.clear();
+                            failures = 0;
+                        }
+                        Ok(PollerCommand::Refresh) | Err(RecvTimeoutError::Timeout) => {}
+                        Err(RecvTimeoutError::Disconnected) => return
+                    }
+                    break;
+                }
+            }
+        });
+        Self { commands, interval_ms }
+    }
+
+    pub fn refresh(&self) {
+        let _ = self.commands.send(PollerCommand::Refresh);
+    }
+
+    // Route every following call to another daemon, forgetting the torrents of the previous one, and poll right away
+    pub fn connect(&self, rpc_client: RpcClient) {
+        let _ = self.commands.send(PollerCommand::Connect(rpc_client));
+    }
+
+    // Also poll the details of the given torrent (or stop doing so with `None`)
+    pub fn focus(&self, torrent_id: Option<i64>) {
+        let _ = self.commands.send(PollerCommand::Focus(torrent_id));
+    }
+
+    // Also poll the session statistics (or stop doing so)
+    pub fn watch_session_stats(&self, is_watching: bool) {
+        let _ = self.commands.send(PollerCommand::WatchSessionStats(is_watching));
+    }
+
+    // Losing focus only delays the next poll, gaining it polls right away
+    pub fn terminal_focus(&self, is_focused: bool) {
+        let _ = self.commands.send(PollerCommand::TerminalFocus(is_focused));
+    }
+
+    pub fn interval_ms(&self) -> u64 {
+        self.interval_ms.load(Ordering::Relaxed)
+    }
+
+    // Halve the interval, returns the new one
+    pub fn faster(&self) -> u64 {
+        self.set_interval_ms(self.interval_ms() / 2)
+    }
+
+    // Double the interval, returns the new one
+    pub fn slower(&self) -> u64 {
+        self.set_interval_ms(self.interval_ms() * 2)
+    }
+
+    fn set_interval_ms(&self, interval_ms: u64) -> u64 {
+        let interval_ms = Self::clamp(interval_ms);
+        self.interval_ms.store(interval_ms, Ordering::Relaxed);
+        self.refresh();
+        interval_ms
+    }
+
+    fn clamp(interval_ms: u64) -> u64 {
+        interval_ms.clamp(MIN_INTERVAL_MS, MAX_INTERVAL_MS)
+    }
+
+    fn backoff(interval_ms: u64, failures: u32, is_terminal_focused: bool) -> Duration {
+        let mut interval_ms = interval_ms << failures.min(MAX_BACKOFF_STEPS);
+        if !is_terminal_focused {
+            interval_ms *= UNFOCUSED_FACTOR;
+        }
+        Duration::from_millis(interval_ms.min(MAX_INTERVAL_MS))
+    }
+
+    // The whole list only once, afterwards just what changed
+    fn poll(
+        transmission_service: &TransmissionService,
+        torrent_store: &TorrentStore,
+        focused_torrent_id: Option<i64>,
+        is_watching_session_stats: bool
+    ) -> DaemonSnapshot {
+        let torrents = if torrent_store.is_populated() {
+            transmission_service
+                .torrent_list_recently_active()
+                .map(|response| torrent_store.apply(response.arguments.torrents, &response.arguments.removed))
+        } else {
+            transmission_service
+                .torrent_list()
+                .map(|response| torrent_store.replace(response.arguments.torrents))
+        };
+        let focused_torrent = focused_torrent_id.map(|torrent_id| {
+            transmission_service
+                .torrent_info(torrent_id.to_string())
+                .map(|response| response.arguments.torrents.first().cloned().unwrap_or_default())
+        });
+        let session_stats = is_watching_session_stats.then(|| {
+            let stats = transmission_service.session_stats()?;
+            let session = transmission_service.session_get(&["download-dir"])?;
+            let free_space = transmission_service.free_space(&session.download_dir)?;
+            Ok(SessionStats { stats, free_space })
+        });
+        DaemonSnapshot { torrents, focused_torrent, session_stats }
+    }
+}
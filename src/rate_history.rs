@@ -0,0 +1,153 @@
+use std::collections::{HashMap, VecDeque};
+use std::sync::{Arc, RwLock};
+use std::time::{Duration, Instant};
+use crate::dto::transmission_dto::TransmissionTorrent;
+
+// Samples older than that are dropped
+pub const HISTORY_WINDOW: Duration = Duration::from_secs(10 * 60);
+
+// Time covered by one column of a graph, however often (or seldom) the daemon is polled
+pub const SLOT: Duration = Duration::from_secs(5);
+
+// Transfer rates (bytes/s) taken at every poll, oldest first
+#[derive(Clone, Default)]
+pub struct RateSamples {
+    samples: VecDeque<(Instant, u64, u64)>
+}
+
+impl RateSamples {
+
+    fn push(&mut self, now: Instant, download: u64, upload: u64) {
+        self.samples.push_back((now, download, upload));
+        while self.samples.front().is_some_and(|(taken, _, _)| now.duration_since(*taken) > HISTORY_WINDOW) {
+            self.samples.pop_front();
+        }
+    }
+
+    /*
+        Download / upload rates of the `count` latest `SLOT`s up to `now`, oldest first. A slot holds the latest sample
+        taken in it and is `None` when no poll happened meanwhile (e.g. while backing off).
+    */
+    pub fn slots(&self, now: Instant, count: usize) -> Vec<Option<(u64, u64)>> {
+        let mut slots = vec![None; count];
+        self.samples.iter().for_each(|(taken, download, upload)| {
+            let age = (now.saturating_duration_since(*taken).as_millis() / SLOT.as_millis()) as usize;
+            if age < count {
+                slots[count - 1 - age] = Some((*download, *upload));
+            }
+        });
+        slots
+    }
+}
+
+/*
+    Ring buffers of the download / upload rates of every torrent and of all of them together, covering
+    the last `HISTORY_WINDOW`. The `Poller` records a sample after every successful poll, the screens only read them.
+*/
+#[derive(Clone, Default)]
+pub struct RateHistory {
+    inner: Arc<RwLock<Inner>>
+}
+
+#[derive(Default)]
+struct Inner {
+    global: RateSamples,
+    torrents: HashMap<i64, RateSamples>
+}
+
+impl RateHistory {
+
+    // Forget everything (e.g. after connecting to another daemon)
+    pub fn clear(&self) {
+        *self.inner.write().unwrap() = Inner::default();
+    }
+
+    // The history of torrents which are gone goes away with them
+    pub fn record(&self, torrents: &[TransmissionTorrent]) {
+        self.record_at(Instant::now(), torrents);
+    }
+
+    fn record_at(&self, now: Instant, torrents: &[TransmissionTorrent]) {
+        let mut inner = self.inner.write().unwrap();
+        inner.torrents.retain(|id, _| torrents.iter().any(|torrent| torrent.id == *id));
+        torrents.iter().for_each(|torrent| {
+            inner.torrents
+                .entry(torrent.id)
+                .or_default()
+                .push(now, torrent.rate_download.max(0) as u64, torrent.rate_upload.max(0) as u64);
+        });
+        let download = torrents.iter().map(|torrent| torrent.rate_download.max(0) as u64).sum();
+        let upload = torrents.iter().map(|torrent| torrent.rate_upload.max(0) as u64).sum();
+        inner.global.push(now, download, upload);
+    }
+
+    pub fn global(&self) -> RateSamples {
+        self.inner.read().unwrap().global.clone()
+    }
+
+    pub fn torrent(&self, torrent_id: i64) -> RateSamples {
+        self.inner.read().unwrap().torrents.get(&torrent_id).cloned().unwrap_or_default()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn torrent(id: i64, rate_download: i64, rate_upload: i64) -> TransmissionTorrent {
+        TransmissionTorrent { id, rate_download, rate_upload, ..Default::default() }
+    }
+
+    #[test]
+    fn slots_keep_the_latest_sample_of_each_slot() {
+        let start = Instant::now();
+        let mut samples = RateSamples::default();
+        samples.push(start, 1, 10);
+        samples.push(start + Duration::from_secs(1), 2, 20);
+        let now = start + SLOT * 2 + Duration::from_secs(1);
+        samples.push(now, 3, 30);
+        assert_eq!(samples.slots(now, 3), vec![Some((2, 20)), None, Some((3, 30))]);
+    }
+
+    #[test]
+    fn slots_only_cover_the_latest_ones() {
+        let start = Instant::now();
+        let mut samples = RateSamples::default();
+        samples.push(start, 1, 1);
+        samples.push(start + SLOT * 5, 2, 2);
+        assert_eq!(samples.slots(start + SLOT * 5, 2), vec![None, Some((2, 2))]);
+        assert_eq!(samples.slots(start + SLOT * 7, 2), vec![None, None]);
+        assert!(samples.slots(start, 0).is_empty());
+    }
+
+    #[test]
+    fn push_drops_samples_older_than_the_window() {
+        let start = Instant::now();
+        let mut samples = RateSamples::default();
+        samples.push(start, 1, 1);
+        samples.push(start + HISTORY_WINDOW, 2, 2);
+        assert_eq!(samples.samples.len(), 2);
+        samples.push(start + HISTORY_WINDOW + Duration::from_secs(1), 3, 3);
+        assert_eq!(samples.samples.iter().map(|(_, download, _)| *download).collect::<Vec<u64>>(), vec![2, 3]);
+    }
+
+    #[test]
+    fn record_sums_the_global_rates_and_forgets_gone_torrents() {
+        let start = Instant::now();
+        let history = RateHistory::default();
+        history.record_at(start, &[torrent(1, 100, 10), torrent(2, 50, -1)]);
+        history.record_at(start + SLOT, &[torrent(2, 70, 5)]);
+        assert_eq!(history.global().slots(start + SLOT, 2), vec![Some((150, 10)), Some((70, 5))]);
+        assert_eq!(history.torrent(2).slots(start + SLOT, 2), vec![Some((50, 0)), Some((70, 5))]);
+        assert!(history.torrent(1).samples.is_empty());
+    }
+
+    #[test]
+    fn clear_forgets_everything() {
+        let history = RateHistory::default();
+        history.record(&[torrent(1, 100, 10)]);
+        history.clear();
+        assert!(history.global().samples.is_empty());
+        assert!(history.torrent(1).samples.is_empty());
+    }
+}
@@ -1,4 +1,5 @@
 use std::collections::HashMap;
+use std::sync::Arc;
 use crate::app::{EmptyRenderableArgs, KeyEventHandler, Renderable, RenderableArgs};
 use crate::screen::key_bindings_block::{KeyBindingItem, KeyBindingsBlock};
 use crossterm::event::{Event, KeyCode, KeyEvent, KeyEventKind};
@@ -12,15 +13,22 @@ use tui_input::backend::crossterm::EventHandler;
 use crate::config::{Config, ConfigKeyBindingKey};
 use crate::service::torrent_service::TorrentService;
 use crate::service::transmission_service::TransmissionService;
+use crate::screen::popup_screen::Notifier;
 
 pub struct AddScreen {
     config_key_bindings: HashMap<ConfigKeyBindingKey, char>,
+    transmission_service: Arc<TransmissionService>,
+    notifier: Notifier,
     input: Input
 }
 
 impl AddScreen {
-    pub fn new(config_key_bindings: HashMap<ConfigKeyBindingKey, char>) -> Self {
-        Self { config_key_bindings, input: Input::new(String::default()) }
+    pub fn new(
+        config_key_bindings: HashMap<ConfigKeyBindingKey, char>,
+        transmission_service: Arc<TransmissionService>,
+        notifier: Notifier
+    ) -> Self {
+        Self { config_key_bindings, transmission_service, notifier, input: Input::new(String::default()) }
     }
 }
 
@@ -81,7 +89,8 @@ impl KeyEventHandler for AddScreen {
             match key_event.code {
                 // submit and leave
                 KeyCode::Enter => {
-                    TransmissionService::torrent_add(self.input.value().to_string());
+                    let result = self.transmission_service.torrent_add(self.input.value().to_string());
+                    self.notifier.torrent_added(&result);
                     self.input.reset();
                     false
                 }
@@ -0,0 +1,447 @@
+use std::collections::{BTreeMap, HashSet};
+use std::sync::Arc;
+use crossterm::event::{Event, KeyCode, KeyEvent, KeyEventKind};
+use ratatui::Frame;
+use ratatui::layout::{Constraint, Layout, Rect};
+use ratatui::prelude::{Color, Line, Modifier, Style, Stylize};
+use ratatui::widgets::{Block, Cell, Padding, Row, Table, TableState};
+use crate::app::KeyEventHandler;
+use crate::dto::transmission_dto::{TransmissionTorrentFiles, PRIORITY_HIGH, PRIORITY_LOW, PRIORITY_NORMAL};
+use crate::poller::Poller;
+use crate::screen::key_bindings_block::KeyBindingsBlock;
+use crate::screen::popup_screen::{Notifier, PopupLevel};
+use crate::service::transmission_service::TransmissionService;
+use crate::util::Util;
+use tui_input::Input;
+use tui_input::backend::crossterm::EventHandler;
+
+// Directory of the tree built from the `/` separated file names
+#[derive(Default)]
+struct Dir {
+    dirs: BTreeMap<String, Dir>,
+    // file name => index of the file in the torrent
+    files: BTreeMap<String, usize>
+}
+
+// One line of the tree, either a directory (standing for every file below it) or a single file
+struct FileRow {
+    path: String,
+    name: String,
+    depth: usize,
+    is_dir: bool,
+    // indices of the files the row stands for, as expected by `torrent-set`
+    files: Vec<usize>,
+    length: i64,
+    bytes_completed: i64,
+    // `None` when the files below a directory disagree
+    wanted: Option<bool>,
+    priority: Option<i64>
+}
+
+/*
+    Files tab of the Info screen, a collapsible directory tree where wanted / unwanted and the
+    priority can be set for a single file or for everything below a directory, and where files and directories can be renamed.
+*/
+pub struct FilesTab {
+    transmission_service: Arc<TransmissionService>,
+    notifier: Notifier,
+    poller: Poller,
+    table_state: TableState,
+    // paths of the collapsed directories
+    collapsed: HashSet<String>,
+    torrent_id: i64,
+    rows: Vec<FileRow>,
+    // new name of the selected row, `None` unless renaming
+    rename_input: Option<Input>,
+    // (path, name) of the row being renamed, taken when renaming starts since the rows are built again on every render
+    rename_target: Option<(String, String)>,
+    // last successful rename (path, name), for the owner of the torrent to apply
+    renamed: Option<(String, String)>
+}
+
+impl FilesTab {
+
+    pub fn new(transmission_service: Arc<TransmissionService>, notifier: Notifier, poller: Poller) -> Self {
+        Self {
+            transmission_service,
+            notifier,
+            poller,
+            table_state: TableState::default().with_selected(0),
+            collapsed: HashSet::new(),
+            torrent_id: 0,
+            rows: vec![],
+            rename_input: None,
+            rename_target: None,
+            renamed: None
+        }
+    }
+
+    // While typing a new name every key belongs to this tab
+    pub fn is_editing(&self) -> bool {
+        self.rename_input.is_some()
+    }
+
+    pub fn take_renamed(&mut self) -> Option<(String, String)> {
+        self.renamed.take()
+    }
+
+    pub fn render(&mut self, frame: &mut Frame, area: Rect, torrent_id: i64, files: &TransmissionTorrentFiles) {
+        if torrent_id != self.torrent_id {
+            self.torrent_id = torrent_id;
+            self.collapsed.clear();
+            self.table_state.select(Some(0));
+            self.rename_input = None;
+            self.rename_target = None;
+        }
+        self.rows = Self::rows(files, &self.collapsed);
+        if self.active_row() >= self.rows.len() {
+            self.table_state.select(Some(self.rows.len().saturating_sub(1)));
+        }
+
+        let key_bindings = vec![
+            KeyBindingsBlock::kb_expand(),
+            KeyBindingsBlock::kb_wanted(),
+            KeyBindingsBlock::kb_priority_high(),
+            KeyBindingsBlock::kb_priority_normal(),
+            KeyBindingsBlock::kb_priority_low(),
+            KeyBindingsBlock::kb_rename(),
+            KeyBindingsBlock::kb_cancel()
+        ];
+        let block = Block::bordered()
+            .title(Line::from(format!(" {} files ", files.files.len()).bold()).centered())
+            .title_bottom(KeyBindingsBlock::key_bindings_as_line(&key_bindings).centered())
+            .padding(Padding::horizontal(1));
+        let input_height = if self.is_editing() { 1 } else { 0 };
+        let [input_area, table_area] = Layout::vertical([Constraint::Length(input_height), Constraint::Fill(1)])
+            .areas(block.inner(area));
+        frame.render_widget(block, area);
+        if let Some(input) = &self.rename_input {
+            Self::render_input(frame, input_area, input);
+        }
+        frame.render_stateful_widget(self.table(), table_area, &mut self.table_state);
+    }
+
+    fn render_input(frame: &mut Frame, area: Rect, input: &Input) {
+        let label = "Rename: ";
+        let width = area.width.saturating_sub(label.len() as u16 + 1);
+        let scroll = input.visual_scroll(width as usize);
+        let value: String = input.value().chars().skip(scroll).collect();
+        frame.render_widget(Line::from(vec![label.bold(), value.into()]), area);
+        let x = input.visual_cursor().max(scroll) - scroll;
+        frame.set_cursor_position((area.x + label.len() as u16 + x as u16, area.y));
+    }
+
+    fn active_row(&self) -> usize {
+        self.table_state.selected().unwrap_or(0)
+    }
+
+    fn next_row(&mut self) {
+        let i = (self.active_row() + 1).min(self.rows.len().saturating_sub(1));
+        self.table_state.select(Some(i));
+    }
+
+    fn previous_row(&mut self) {
+        self.table_state.select(Some(self.active_row().saturating_sub(1)));
+    }
+
+    fn rows(files: &TransmissionTorrentFiles, collapsed: &HashSet<String>) -> Vec<FileRow> {
+        let mut root = Dir::default();
+        files.files.iter().enumerate().for_each(|(index, file)| {
+            let mut parts: Vec<&str> = file.name.split('/').collect();
+            let file_name = parts.pop().unwrap_or_default();
+            let dir = parts.into_iter().fold(&mut root, |dir, part| dir.dirs.entry(part.to_string()).or_default());
+            dir.files.insert(file_name.to_string(), index);
+        });
+        let mut rows = vec![];
+        Self::flatten(&root, "", 0, true, files, collapsed, &mut rows);
+        rows
+    }
+
+    // Returns the indices of every file below `dir`, rows are only added for what is visible
+    fn flatten(
+        dir: &Dir,
+        prefix: &str,
+        depth: usize,
+        is_visible: bool,
+        files: &TransmissionTorrentFiles,
+        collapsed: &HashSet<String>,
+        rows: &mut Vec<FileRow>
+    ) -> Vec<usize> {
+        let mut indices = vec![];
+        dir.dirs.iter().for_each(|(name, sub_dir)| {
+            let path = format!("{prefix}{name}/");
+            let row_index = rows.len();
+            let is_expanded = !collapsed.contains(&path);
+            let sub_indices = Self::flatten(sub_dir, &path, depth + 1, is_visible && is_expanded, files, collapsed, rows);
+            if is_visible {
+                rows.insert(row_index, Self::row(path, name, depth, true, sub_indices.clone(), files));
+            }
+            indices.extend(sub_indices);
+        });
+        dir.files.iter().for_each(|(name, index)| {
+            if is_visible {
+                rows.push(Self::row(format!("{prefix}{name}"), name, depth, false, vec![*index], files));
+            }
+            indices.push(*index);
+        });
+        indices
+    }
+
+    fn row(path: String, name: &str, depth: usize, is_dir: bool, indices: Vec<usize>, files: &TransmissionTorrentFiles) -> FileRow {
+        let all_same = |values: Vec<i64>| match values.first() {
+            Some(first) if values.iter().all(|value| value == first) => Some(*first),
+            _ => None
+        };
+        let wanted = all_same(indices.iter().map(|i| files.wanted.get(*i).copied().unwrap_or(true) as i64).collect());
+        let priority = all_same(indices.iter().map(|i| files.priorities.get(*i).copied().unwrap_or(PRIORITY_NORMAL)).collect());
+        FileRow {
+            path,
+            name: name.to_string(),
+            depth,
+            is_dir,
+            length: indices.iter().filter_map(|i| files.files.get(*i)).map(|file| file.length).sum(),
+            bytes_completed: indices.iter().filter_map(|i| files.files.get(*i)).map(|file| file.bytes_completed).sum(),
+            files: indices,
+            wanted: wanted.map(|wanted| wanted != 0),
+            priority
+        }
+    }
+
+    fn table(&self) -> Table<'static> {
+        let rows = self.rows.iter().map(|row| {
+            let icon = match (row.is_dir, self.collapsed.contains(&row.path)) {
+                (true, true) => "\u{25b8} ",
+                (true, false) => "\u{25be} ",
+                _ => "  "
+            };
+            let progress = if row.length > 0 { 100f64 * row.bytes_completed as f64 / row.length as f64 } else { 100f64 };
+            let wanted = match row.wanted {
+                Some(true) => "yes",
+                Some(false) => "no",
+                None => "mixed"
+            };
+            let priority = match row.priority {
+                Some(PRIORITY_HIGH) => "high",
+                Some(PRIORITY_LOW) => "low",
+                Some(_) => "normal",
+                None => "mixed"
+            };
+            let cells = vec![
+                Cell::from(format!("{}{}{}", "  ".repeat(row.depth), icon, row.name)),
+                Cell::from(format!("{:.2} %", progress)),
+                Cell::from(Util::print_bytes(row.length as f64)),
+                Cell::from(wanted),
+                Cell::from(priority),
+            ];
+            let row_ui = Row::new(cells);
+            if row.wanted == Some(false) { row_ui.dim() } else { row_ui }
+        });
+        let header = ["Name", "Done", "Size", "Wanted", "Priority"]
+            .into_iter()
+            .map(Cell::from)
+            .collect::<Row>()
+            .height(1)
+            .bg(Color::Indexed(236)) // https://en.wikipedia.org/wiki/ANSI_escape_code#Colors
+            .fg(Color::Indexed(255));
+        let selected_row_style = Style::default()
+            .add_modifier(Modifier::REVERSED)
+            .fg(Color::Indexed(255)) // https://en.wikipedia.org/wiki/ANSI_escape_code#Colors
+            .bg(Color::Black);
+        Table::new(
+            rows,
+            [
+                Constraint::Fill(1),
+                Constraint::Length(10),
+                Constraint::Length(12),
+                Constraint::Length(8),
+                Constraint::Length(10),
+            ],
+        )
+        .header(header)
+        .row_highlight_style(selected_row_style)
+    }
+
+    fn toggle_collapsed(&mut self) {
+        if let Some(row) = self.rows.get(self.active_row()) && row.is_dir && !self.collapsed.remove(&row.path) {
+            self.collapsed.insert(row.path.clone());
+        }
+    }
+
+    // A (partly) unwanted directory becomes wanted as a whole, a wanted one unwanted
+    fn toggle_wanted(&mut self) {
+        let Some(row) = self.rows.get(self.active_row()) else {
+            return;
+        };
+        let wanted = row.wanted != Some(true);
+        let result = self.transmission_service.torrent_set_files_wanted(self.torrent_id, &row.files, wanted);
+        let done = if wanted { "Wanted" } else { "Unwanted" };
+        self.notifier.result(&result, format!("{} {}", done, row.name));
+        self.poller.refresh();
+    }
+
+    fn set_priority(&mut self, priority: i64) {
+        let Some(row) = self.rows.get(self.active_row()) else {
+            return;
+        };
+        let result = self.transmission_service.torrent_set_files_priority(self.torrent_id, &row.files, priority);
+        self.notifier.result(&result, format!("Changed priority of {}", row.name));
+        self.poller.refresh();
+    }
+
+    fn edit_name(&mut self) {
+        if let Some(row) = self.rows.get(self.active_row()) {
+            self.rename_input = Some(Input::new(row.name.clone()));
+            self.rename_target = Some((row.path.clone(), row.name.clone()));
+        }
+    }
+
+    /*
+        Path of a row as `torrent-rename-path` expects it and the path of the row once renamed,
+        directories being only told apart by their trailing slash.
+    */
+    fn rename_paths(row_path: &str, name: &str) -> (String, String) {
+        let path = row_path.trim_end_matches('/');
+        let parent = path.rsplit_once('/').map(|(parent, _)| format!("{parent}/")).unwrap_or_default();
+        let suffix = if row_path.ends_with('/') { "/" } else { "" };
+        (path.to_string(), format!("{parent}{name}{suffix}"))
+    }
+
+    fn rename(&mut self, input: Input) {
+        let Some((row_path, row_name)) = self.rename_target.take() else {
+            return;
+        };
+        let name = match Util::validate_file_name(input.value()) {
+            Ok(name) if name == row_name => return,
+            Ok(name) => name,
+            Err(e) => {
+                self.notifier.toast(PopupLevel::Error, e);
+                return;
+            }
+        };
+        let (path, renamed) = Self::rename_paths(&row_path, &name);
+        let result = self.transmission_service.torrent_rename_path(self.torrent_id, &path, &name);
+        self.notifier.result(&result, format!("Renamed {} to {}", row_name, name));
+        if result.is_ok() {
+            // keep a renamed directory expanded or collapsed
+            if self.collapsed.remove(&row_path) {
+                self.collapsed.insert(renamed);
+            }
+            self.renamed = Some((path, name));
+        }
+        self.poller.refresh();
+    }
+}
+
+impl KeyEventHandler for FilesTab {
+    /*
+       Returns false if we are done from this screen
+    */
+    fn handle_key_event(&mut self, key_event: KeyEvent, event: Event) -> bool {
+        if key_event.kind == KeyEventKind::Press && let Some(mut input) = self.rename_input.take() {
+            match key_event.code {
+                KeyCode::Enter => self.rename(input),
+                KeyCode::Esc => {}
+                // let input handle it
+                _ => {
+                    input.handle_event(&event);
+                    self.rename_input = Some(input);
+                }
+            }
+            return true;
+        }
+        if key_event.kind == KeyEventKind::Press {
+            match key_event.code {
+                KeyCode::Char('j') | KeyCode::Down => self.next_row(),
+                KeyCode::Char('k') | KeyCode::Up => self.previous_row(),
+                KeyCode::Enter => self.toggle_collapsed(),
+                KeyCode::Char(' ') => self.toggle_wanted(),
+                KeyCode::Char('h') => self.set_priority(PRIORITY_HIGH),
+                KeyCode::Char('n') => self.set_priority(PRIORITY_NORMAL),
+                KeyCode::Char('l') => self.set_priority(PRIORITY_LOW),
+                KeyCode::Char('r') => self.edit_name(),
+                // leave
+                KeyCode::Esc => return false,
+                _ => {}
+            }
+        }
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::dto::transmission_dto::TransmissionTorrentFile;
+    use super::*;
+
+    fn file(name: &str, length: i64, bytes_completed: i64) -> TransmissionTorrentFile {
+        TransmissionTorrentFile { name: name.to_string(), length, bytes_completed, ..Default::default() }
+    }
+
+    // Listed out of order, as a torrent may have them
+    fn files() -> TransmissionTorrentFiles {
+        TransmissionTorrentFiles {
+            files: vec![
+                file("show/s01/e02.mkv", 200, 200),
+                file("show/notes.txt", 10, 0),
+                file("show/s01/e01.mkv", 100, 50),
+                file("show/s02/e01.mkv", 300, 0),
+            ],
+            name: "show".to_string(),
+            priorities: vec![PRIORITY_HIGH, PRIORITY_NORMAL, PRIORITY_HIGH, PRIORITY_LOW],
+            wanted: vec![true, true, true, false]
+        }
+    }
+
+    fn paths(rows: &[FileRow]) -> Vec<(usize, &str)> {
+        rows.iter().map(|row| (row.depth, row.path.as_str())).collect()
+    }
+
+    #[test]
+    fn builds_the_tree_with_directories_before_files() {
+        let rows = FilesTab::rows(&files(), &HashSet::new());
+        assert_eq!(paths(&rows), vec![
+            (0, "show/"),
+            (1, "show/s01/"),
+            (2, "show/s01/e01.mkv"),
+            (2, "show/s01/e02.mkv"),
+            (1, "show/s02/"),
+            (2, "show/s02/e01.mkv"),
+            (1, "show/notes.txt"),
+        ]);
+        assert_eq!(rows[2].name, "e01.mkv");
+        assert!(rows[1].is_dir && !rows[2].is_dir);
+    }
+
+    #[test]
+    fn a_directory_stands_for_every_file_below_it() {
+        let rows = FilesTab::rows(&files(), &HashSet::new());
+        assert_eq!(rows[0].files, vec![2, 0, 3, 1]);
+        assert_eq!(rows[1].files, vec![2, 0]);
+        assert_eq!((rows[1].length, rows[1].bytes_completed), (300, 250));
+        assert_eq!((rows[1].wanted, rows[1].priority), (Some(true), Some(PRIORITY_HIGH)));
+        assert_eq!((rows[0].wanted, rows[0].priority), (None, None));
+    }
+
+    #[test]
+    fn a_collapsed_directory_hides_its_content_but_still_stands_for_it() {
+        let collapsed = HashSet::from(["show/s01/".to_string()]);
+        let rows = FilesTab::rows(&files(), &collapsed);
+        assert_eq!(paths(&rows), vec![
+            (0, "show/"),
+            (1, "show/s01/"),
+            (1, "show/s02/"),
+            (2, "show/s02/e01.mkv"),
+            (1, "show/notes.txt"),
+        ]);
+        assert_eq!(rows[1].files, vec![2, 0]);
+        assert_eq!(rows[0].files, vec![2, 0, 3, 1]);
+    }
+
+    #[test]
+    fn rename_paths_keep_the_parent_and_the_trailing_slash_of_directories() {
+        assert_eq!(FilesTab::rename_paths("show/s01/", "season 1"), ("show/s01".to_string(), "show/season 1/".to_string()));
+        assert_eq!(FilesTab::rename_paths("show/s01/e01.mkv", "pilot.mkv"), ("show/s01/e01.mkv".to_string(), "show/s01/pilot.mkv".to_string()));
+        assert_eq!(FilesTab::rename_paths("show/", "series"), ("show".to_string(), "series/".to_string()));
+        assert_eq!(FilesTab::rename_paths("single.iso", "debian.iso"), ("single.iso".to_string(), "debian.iso".to_string()));
+    }
+}
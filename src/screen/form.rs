@@ -0,0 +1,417 @@
+use std::collections::BTreeMap;
+use crossterm::event::{Event, KeyCode, KeyEvent, KeyEventKind};
+use ratatui::Frame;
+use ratatui::layout::{Constraint, Layout, Rect};
+use ratatui::prelude::{Color, Line, Modifier, Style, Stylize};
+use ratatui::widgets::{Cell, Paragraph, Row, Table, TableState};
+use serde_json::{json, Map, Value};
+use tui_input::Input;
+use tui_input::backend::crossterm::EventHandler;
+use crate::screen::key_bindings_block::{KeyBindingItem, KeyBindingsBlock};
+use crate::screen::popup_screen::{Notifier, PopupLevel};
+
+#[derive(Clone, Copy)]
+pub enum FieldKind {
+    Bool,
+    Int { min: i64, max: i64 },
+    Float,
+    Text,
+    // minutes after midnight, edited as "HH:MM"
+    Time,
+    // one of the given strings
+    Choice(&'static [&'static str]),
+    // one of the given numbers, shown by name
+    Enum(&'static [(i64, &'static str)])
+}
+
+pub struct Field {
+    // name used by the daemon (e.g. with `session-get` / `session-set`)
+    pub key: &'static str,
+    pub title: &'static str,
+    pub kind: FieldKind
+}
+
+pub const fn field(key: &'static str, title: &'static str, kind: FieldKind) -> Field {
+    Field { key, title, kind }
+}
+
+pub const COUNT: FieldKind = FieldKind::Int { min: 0, max: i32::MAX as i64 };
+
+// Title and fields of a group of rows
+pub type Section = (&'static str, &'static [Field]);
+
+// What the owner of the form has to do after a key
+#[derive(PartialEq)]
+pub enum FormAction {
+    None,
+    // send `edits` to the daemon
+    Apply,
+    // fetch the values again (`reset`)
+    Reload,
+    Leave
+}
+
+#[derive(Clone, Copy, PartialEq)]
+enum Mode {
+    Browse,
+    // typing a new value for the selected field
+    Edit,
+    // showing what applying would change
+    Preview
+}
+
+/*
+    Table of typed fields grouped in sections, over values keyed like the daemon keys them.
+    Edits are validated and kept apart from the values until previewed (old and new value of every change)
+    and confirmed, talking to the daemon is up to the owner of the form.
+*/
+pub struct Form {
+    sections: &'static [Section],
+    notifier: Notifier,
+    table_state: TableState,
+    // index in the fields of all sections (the section rows are not selectable)
+    selected: usize,
+    values: Map<String, Value>,
+    // changed fields only, keyed like `values`
+    edits: BTreeMap<&'static str, Value>,
+    input: Input,
+    mode: Mode
+}
+
+impl Form {
+
+    pub fn new(sections: &'static [Section], notifier: Notifier) -> Self {
+        Self {
+            sections,
+            notifier,
+            table_state: TableState::default(),
+            selected: 0,
+            values: Map::new(),
+            edits: BTreeMap::new(),
+            input: Input::default(),
+            mode: Mode::Browse
+        }
+    }
+
+    pub fn keys(&self) -> Vec<&'static str> {
+        self.fields().map(|field| field.key).collect()
+    }
+
+    // Start over from the given values, dropping every edit
+    pub fn reset(&mut self, values: Map<String, Value>) {
+        self.values = values;
+        self.edits.clear();
+        self.mode = Mode::Browse;
+    }
+
+    // Back from the preview (e.g. when applying failed), the edits are kept
+    pub fn browse(&mut self) {
+        self.mode = Mode::Browse;
+    }
+
+    pub fn edits(&self) -> Map<String, Value> {
+        self.edits.iter().map(|(key, value)| (key.to_string(), value.clone())).collect()
+    }
+
+    pub fn edit_count(&self) -> usize {
+        self.edits.len()
+    }
+
+    pub fn is_previewing(&self) -> bool {
+        self.mode == Mode::Preview
+    }
+
+    pub fn key_bindings(&self) -> Vec<KeyBindingItem> {
+        match self.mode {
+            Mode::Preview => vec![KeyBindingsBlock::kb_confirm(), KeyBindingsBlock::kb_cancel()],
+            _ => vec![KeyBindingsBlock::kb_edit(), KeyBindingsBlock::kb_apply(), KeyBindingsBlock::kb_refresh()]
+        }
+    }
+
+    fn fields(&self) -> impl Iterator<Item = &'static Field> {
+        self.sections.iter().flat_map(|(_, fields)| fields.iter())
+    }
+
+    fn selected_field(&self) -> &'static Field {
+        self.fields().nth(self.selected).unwrap_or(&self.sections[0].1[0])
+    }
+
+    fn original(&self, key: &str) -> Value {
+        self.values.get(key).cloned().unwrap_or(Value::Null)
+    }
+
+    fn current(&self, key: &str) -> Value {
+        self.edits.get(key).cloned().unwrap_or_else(|| self.original(key))
+    }
+
+    // Compared as displayed, the daemon may send `2` where we would send `2.0`
+    fn set(&mut self, field: &'static Field, value: Value) {
+        if Self::display(field.kind, &value) == Self::display(field.kind, &self.original(field.key)) {
+            self.edits.remove(field.key);
+        } else {
+            self.edits.insert(field.key, value);
+        }
+    }
+
+    fn display(kind: FieldKind, value: &Value) -> String {
+        match (kind, value) {
+            (_, Value::Null) => "-".to_string(),
+            (FieldKind::Bool, Value::Bool(value)) => if *value { "yes" } else { "no" }.to_string(),
+            (FieldKind::Float, Value::Number(value)) => format!("{:.2}", value.as_f64().unwrap_or_default()),
+            (FieldKind::Time, Value::Number(value)) => {
+                let minutes = value.as_i64().unwrap_or_default();
+                format!("{:0>2}:{:0>2}", minutes / 60, minutes % 60)
+            }
+            (FieldKind::Enum(names), Value::Number(value)) => names
+                .iter()
+                .find(|(number, _)| Some(*number) == value.as_i64())
+                .map(|(_, name)| name.to_string())
+                .unwrap_or(value.to_string()),
+            (_, Value::String(value)) => value.clone(),
+            (_, value) => value.to_string()
+        }
+    }
+
+    // Validate what was typed, the error tells what is expected
+    fn parse(kind: FieldKind, text: &str) -> Result<Value, String> {
+        let text = text.trim();
+        match kind {
+            FieldKind::Int { min, max } => match text.parse::<i64>() {
+                Ok(value) if (min..=max).contains(&value) => Ok(json!(value)),
+                _ => Err(format!("Expected a whole number between {} and {}", min, max))
+            },
+            FieldKind::Float => match text.parse::<f64>() {
+                Ok(value) if value >= 0f64 => Ok(json!(value)),
+                _ => Err("Expected a positive number".to_string())
+            },
+            FieldKind::Time => {
+                let time = text.split_once(':').and_then(|(hours, minutes)| Some((hours.parse::<i64>().ok()?, minutes.parse::<i64>().ok()?)));
+                match time {
+                    Some((hours, minutes)) if (0..24).contains(&hours) && (0..60).contains(&minutes) => Ok(json!(hours * 60 + minutes)),
+                    _ => Err("Expected a time as HH:MM".to_string())
+                }
+            }
+            FieldKind::Text if text.is_empty() => Err("Expected a value".to_string()),
+            FieldKind::Text => Ok(json!(text)),
+            FieldKind::Bool | FieldKind::Choice(_) | FieldKind::Enum(_) => Err("Not editable as text".to_string())
+        }
+    }
+
+    // Booleans and choices change in place, everything else is typed
+    fn edit(&mut self) {
+        let field = self.selected_field();
+        let current = self.current(field.key);
+        match field.kind {
+            FieldKind::Bool => self.set(field, json!(!current.as_bool().unwrap_or_default())),
+            FieldKind::Choice(choices) => {
+                let i = choices.iter().position(|choice| Some(*choice) == current.as_str()).map(|i| i + 1).unwrap_or(0);
+                self.set(field, json!(choices[i % choices.len()]));
+            }
+            FieldKind::Enum(names) => {
+                let i = names.iter().position(|(number, _)| Some(*number) == current.as_i64()).map(|i| i + 1).unwrap_or(0);
+                self.set(field, json!(names[i % names.len()].0));
+            }
+            kind => {
+                self.input = Input::new(Self::display(kind, &current));
+                self.mode = Mode::Edit;
+            }
+        }
+    }
+
+    fn apply_input(&mut self) {
+        let field = self.selected_field();
+        match Self::parse(field.kind, self.input.value()) {
+            Ok(value) => {
+                self.set(field, value);
+                self.mode = Mode::Browse;
+            }
+            Err(e) => self.notifier.toast(PopupLevel::Error, format!("{}: {}", field.title, e))
+        }
+    }
+
+    fn preview(&mut self) {
+        if self.edits.is_empty() {
+            self.notifier.toast(PopupLevel::Info, "Nothing changed");
+        } else {
+            self.mode = Mode::Preview;
+        }
+    }
+
+    pub fn render(&mut self, frame: &mut Frame, area: Rect) {
+        if self.mode == Mode::Preview {
+            self.render_preview(frame, area);
+            return;
+        }
+
+        let input_height = if self.mode == Mode::Edit { 2 } else { 0 };
+        let [input_area, table_area] = Layout::vertical([Constraint::Length(input_height), Constraint::Fill(1)]).areas(area);
+        if self.mode == Mode::Edit {
+            self.render_input(frame, input_area);
+        }
+        let (table, selected_row) = self.table();
+        self.table_state.select(selected_row);
+        frame.render_stateful_widget(table, table_area, &mut self.table_state);
+    }
+
+    // Also returns the row of the selected field
+    fn table(&self) -> (Table<'static>, Option<usize>) {
+        let mut rows = vec![];
+        let mut selected_row = None;
+        let mut i = 0;
+        self.sections.iter().for_each(|(section, fields)| {
+            rows.push(Row::new(vec![Cell::from(section.to_string().bold())]));
+            fields.iter().for_each(|field| {
+                if i == self.selected {
+                    selected_row = Some(rows.len());
+                }
+                let value = Self::display(field.kind, &self.current(field.key));
+                let value = if self.edits.contains_key(field.key) {
+                    Cell::from(format!("{} *", value).yellow())
+                } else {
+                    Cell::from(value)
+                };
+                rows.push(Row::new(vec![
+                    Cell::from(format!("  {}", field.title)),
+                    value,
+                    Cell::from(field.key.dim()),
+                ]));
+                i += 1;
+            });
+        });
+        let header = ["Setting", "Value", "Key"]
+            .into_iter()
+            .map(Cell::from)
+            .collect::<Row>()
+            .height(1)
+            .bg(Color::Indexed(236)) // https://en.wikipedia.org/wiki/ANSI_escape_code#Colors
+            .fg(Color::Indexed(255));
+        let selected_row_style = Style::default()
+            .add_modifier(Modifier::REVERSED)
+            .fg(Color::Indexed(255)) // https://en.wikipedia.org/wiki/ANSI_escape_code#Colors
+            .bg(Color::Black);
+        let table = Table::new(rows, [Constraint::Fill(1), Constraint::Fill(1), Constraint::Length(28)])
+            .header(header)
+            .row_highlight_style(selected_row_style);
+        (table, selected_row)
+    }
+
+    // Old and new value of every changed field
+    fn render_preview(&self, frame: &mut Frame, area: Rect) {
+        let mut lines = vec![Line::from("Apply these changes?".bold()), Line::from("")];
+        self.fields()
+            .filter_map(|field| self.edits.get(field.key).map(|value| (field, value)))
+            .for_each(|(field, value)| {
+                lines.push(Line::from(vec![
+                    format!("{}: ", field.title).bold(),
+                    Self::display(field.kind, &self.original(field.key)).red(),
+                    " \u{2192} ".into(),
+                    Self::display(field.kind, value).green(),
+                ]));
+            });
+        frame.render_widget(Paragraph::new(lines), area);
+    }
+
+    fn render_input(&self, frame: &mut Frame, area: Rect) {
+        let label = format!("{}: ", self.selected_field().title);
+        let width = area.width.saturating_sub(label.len() as u16 + 1);
+        let scroll = self.input.visual_scroll(width as usize);
+        let value: String = self.input.value().chars().skip(scroll).collect();
+        let x = self.input.visual_cursor().max(scroll) - scroll;
+        frame.set_cursor_position((area.x + label.len() as u16 + x as u16, area.y));
+        frame.render_widget(Line::from(vec![label.bold(), value.into()]), area);
+    }
+
+    pub fn handle_key_event(&mut self, key_event: KeyEvent, event: Event) -> FormAction {
+        if key_event.kind != KeyEventKind::Press {
+            return FormAction::None;
+        }
+        match (self.mode, key_event.code) {
+            (Mode::Edit, KeyCode::Enter) => self.apply_input(),
+            (Mode::Edit, KeyCode::Esc) => self.mode = Mode::Browse,
+            // let input handle it
+            (Mode::Edit, _) => {
+                self.input.handle_event(&event);
+            }
+            (Mode::Preview, KeyCode::Enter) => return FormAction::Apply,
+            (Mode::Preview, KeyCode::Esc) => self.mode = Mode::Browse,
+            (Mode::Preview, _) => {}
+            (Mode::Browse, KeyCode::Char('j') | KeyCode::Down) => {
+                self.selected = (self.selected + 1).min(self.fields().count() - 1);
+            }
+            (Mode::Browse, KeyCode::Char('k') | KeyCode::Up) => self.selected = self.selected.saturating_sub(1),
+            (Mode::Browse, KeyCode::Enter) => self.edit(),
+            (Mode::Browse, KeyCode::Char('a')) => self.preview(),
+            (Mode::Browse, KeyCode::Char('r')) => return FormAction::Reload,
+            (Mode::Browse, KeyCode::Esc) => return FormAction::Leave,
+            (Mode::Browse, _) => {}
+        }
+        FormAction::None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PERCENT: FieldKind = FieldKind::Int { min: 0, max: 100 };
+
+    #[test]
+    fn parses_whole_numbers_within_their_range() {
+        assert_eq!(Form::parse(PERCENT, "0"), Ok(json!(0)));
+        assert_eq!(Form::parse(PERCENT, " 100 "), Ok(json!(100)));
+        assert_eq!(Form::parse(COUNT, "2147483647"), Ok(json!(i32::MAX)));
+    }
+
+    #[test]
+    fn rejects_whole_numbers_out_of_range_or_malformed() {
+        assert!(Form::parse(PERCENT, "101").is_err());
+        assert!(Form::parse(PERCENT, "-1").is_err());
+        assert!(Form::parse(COUNT, "2147483648").is_err());
+        assert!(Form::parse(PERCENT, "1.5").is_err());
+        assert!(Form::parse(PERCENT, "ten").is_err());
+        assert!(Form::parse(PERCENT, "").is_err());
+    }
+
+    #[test]
+    fn parses_positive_numbers() {
+        assert_eq!(Form::parse(FieldKind::Float, "1.5"), Ok(json!(1.5)));
+        assert_eq!(Form::parse(FieldKind::Float, "2"), Ok(json!(2.0)));
+        assert_eq!(Form::parse(FieldKind::Float, "0"), Ok(json!(0.0)));
+    }
+
+    #[test]
+    fn rejects_negative_or_malformed_numbers() {
+        assert!(Form::parse(FieldKind::Float, "-0.5").is_err());
+        assert!(Form::parse(FieldKind::Float, "1,5").is_err());
+        assert!(Form::parse(FieldKind::Float, "").is_err());
+    }
+
+    #[test]
+    fn parses_times_as_minutes_after_midnight() {
+        assert_eq!(Form::parse(FieldKind::Time, "00:00"), Ok(json!(0)));
+        assert_eq!(Form::parse(FieldKind::Time, "9:05"), Ok(json!(545)));
+        assert_eq!(Form::parse(FieldKind::Time, "23:59"), Ok(json!(1439)));
+    }
+
+    #[test]
+    fn rejects_times_out_of_range_or_malformed() {
+        assert!(Form::parse(FieldKind::Time, "24:00").is_err());
+        assert!(Form::parse(FieldKind::Time, "12:60").is_err());
+        assert!(Form::parse(FieldKind::Time, "-1:30").is_err());
+        assert!(Form::parse(FieldKind::Time, "1230").is_err());
+        assert!(Form::parse(FieldKind::Time, "noon").is_err());
+    }
+
+    #[test]
+    fn parses_trimmed_text_but_not_empty_text() {
+        assert_eq!(Form::parse(FieldKind::Text, " /data/torrents "), Ok(json!("/data/torrents")));
+        assert!(Form::parse(FieldKind::Text, "   ").is_err());
+    }
+
+    #[test]
+    fn toggled_kinds_are_not_typed() {
+        assert!(Form::parse(FieldKind::Bool, "yes").is_err());
+        assert!(Form::parse(FieldKind::Choice(&["required", "preferred"]), "required").is_err());
+        assert!(Form::parse(FieldKind::Enum(&[(0, "global")]), "0").is_err());
+    }
+}
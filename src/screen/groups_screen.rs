@@ -0,0 +1,427 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+use crossterm::event::{Event, KeyCode, KeyEvent, KeyEventKind};
+use ratatui::Frame;
+use ratatui::layout::{Constraint, Layout, Rect};
+use ratatui::prelude::{Color, Line, Modifier, Style, Stylize};
+use ratatui::symbols::border;
+use ratatui::widgets::{Block, Cell, Padding, Paragraph, Row, Table, TableState};
+use serde_json::{json, Map, Value};
+use tui_input::Input;
+use tui_input::backend::crossterm::EventHandler;
+use crate::app::{EmptyRenderableArgs, KeyEventHandler, Renderable};
+use crate::config::ConfigKeyBindingKey;
+use crate::dto::transmission_dto::{TransmissionGroup, TransmissionTorrent};
+use crate::error::RustorResult;
+use crate::poller::Poller;
+use crate::screen::form::{field, FieldKind, Form, FormAction, Section, COUNT};
+use crate::screen::key_bindings_block::KeyBindingsBlock;
+use crate::screen::popup_screen::{Notifier, PopupLevel};
+use crate::service::transmission_service::TransmissionService;
+use crate::store::TorrentStore;
+use crate::util::Util;
+
+// See `group-set` in https://github.com/transmission/transmission/blob/main/docs/rpc-spec.md
+const SECTIONS: &[Section] = &[
+    ("Speed limits", &[
+        field("speed-limit-down-enabled", "Limit download", FieldKind::Bool),
+        field("speed-limit-down", "Download limit (kB/s)", COUNT),
+        field("speed-limit-up-enabled", "Limit upload", FieldKind::Bool),
+        field("speed-limit-up", "Upload limit (kB/s)", COUNT),
+        field("honorsSessionLimits", "Honor session limits", FieldKind::Bool),
+    ]),
+];
+
+// member torrents listed below the table, with their title
+const MEMBERS_HEIGHT: u16 = 10;
+
+#[derive(Clone, Copy, PartialEq)]
+enum Mode {
+    Browse,
+    // typing the name of a new group
+    Name,
+    // limits of the selected group in the form
+    Edit
+}
+
+/*
+    Bandwidth groups of the daemon (rpc-version 17), loaded with `group-get` when the screen opens.
+    A new group is created with `group-set` right away and its limits are then edited like the Settings, the torrents
+    marked in Home (or the selected one) can be assigned to the selected group or taken out of theirs with `torrent-set`.
+*/
+pub struct GroupsScreen {
+    config_key_bindings: HashMap<ConfigKeyBindingKey, char>,
+    transmission_service: Arc<TransmissionService>,
+    notifier: Notifier,
+    poller: Poller,
+    torrent_store: TorrentStore,
+    table_state: TableState,
+    groups: RustorResult<Vec<TransmissionGroup>>,
+    // the torrents of Home to assign, as marked when the screen opened
+    tor_ids: Vec<i64>,
+    name_input: Input,
+    form: Form,
+    mode: Mode
+}
+
+impl GroupsScreen {
+
+    pub fn new(
+        config_key_bindings: HashMap<ConfigKeyBindingKey, char>,
+        transmission_service: Arc<TransmissionService>,
+        notifier: Notifier,
+        poller: Poller,
+        torrent_store: TorrentStore
+    ) -> Self {
+        Self {
+            config_key_bindings,
+            transmission_service,
+            form: Form::new(SECTIONS, notifier.clone()),
+            notifier,
+            poller,
+            torrent_store,
+            table_state: TableState::default().with_selected(0),
+            groups: Ok(vec![]),
+            tor_ids: vec![],
+            name_input: Input::default(),
+            mode: Mode::Browse
+        }
+    }
+
+    // Fetch the groups, `tor_ids` are those to assign
+    pub fn load(&mut self, tor_ids: Vec<i64>) {
+        self.tor_ids = tor_ids;
+        self.reload();
+    }
+
+    fn reload(&mut self) {
+        self.groups = self.transmission_service.group_get();
+        self.mode = Mode::Browse;
+        let count = self.groups.as_ref().map(Vec::len).unwrap_or_default();
+        if self.active_row() >= count {
+            self.table_state.select(Some(count.saturating_sub(1)));
+        }
+    }
+
+    fn active_row(&self) -> usize {
+        self.table_state.selected().unwrap_or(0)
+    }
+
+    fn next_row(&mut self) {
+        let count = self.groups.as_ref().map(Vec::len).unwrap_or_default();
+        self.table_state.select(Some((self.active_row() + 1).min(count.saturating_sub(1))));
+    }
+
+    fn previous_row(&mut self) {
+        self.table_state.select(Some(self.active_row().saturating_sub(1)));
+    }
+
+    fn active_group(&self) -> Option<TransmissionGroup> {
+        self.groups.as_ref().ok().and_then(|groups| groups.get(self.active_row()).cloned())
+    }
+
+    // Number of torrents in each group, counted once per render rather than once per row
+    fn member_counts(torrents: &[TransmissionTorrent]) -> HashMap<&str, usize> {
+        let mut counts = HashMap::new();
+        torrents.iter().for_each(|torrent| *counts.entry(torrent.group.as_str()).or_default() += 1);
+        counts
+    }
+
+    // Keyed like `group-set` expects them
+    fn values(group: &TransmissionGroup) -> Map<String, Value> {
+        let values = json!({
+            "speed-limit-down-enabled": group.speed_limit_down_enabled,
+            "speed-limit-down": group.speed_limit_down,
+            "speed-limit-up-enabled": group.speed_limit_up_enabled,
+            "speed-limit-up": group.speed_limit_up,
+            "honorsSessionLimits": group.honors_session_limits
+        });
+        values.as_object().cloned().unwrap_or_default()
+    }
+
+    fn edit(&mut self) {
+        if let Some(group) = self.active_group() {
+            self.form.reset(Self::values(&group));
+            self.mode = Mode::Edit;
+        }
+    }
+
+    fn validate_name(name: &str, groups: &[TransmissionGroup]) -> Result<String, String> {
+        let name = name.trim();
+        if name.is_empty() {
+            return Err("Expected a name".to_string());
+        }
+        if groups.iter().any(|group| group.name == name) {
+            return Err(format!("Group {} already exists", name));
+        }
+        Ok(name.to_string())
+    }
+
+    // The daemon creates the group with its default limits, which are edited next
+    fn create(&mut self) {
+        let groups = self.groups.as_deref().unwrap_or_default();
+        let name = match Self::validate_name(self.name_input.value(), groups) {
+            Ok(name) => name,
+            Err(e) => {
+                self.notifier.toast(PopupLevel::Error, e);
+                return;
+            }
+        };
+        let result = self.transmission_service.group_set(&name, Map::new());
+        self.notifier.result(&result, format!("Created group {}", name));
+        if result.is_ok() {
+            self.reload();
+            let row = self.groups.as_ref().ok().and_then(|groups| groups.iter().position(|group| group.name == name));
+            self.table_state.select(row);
+            self.edit();
+        }
+    }
+
+    fn apply(&mut self) {
+        let Some(group) = self.active_group() else {
+            return;
+        };
+        let result = self.transmission_service.group_set(&group.name, self.form.edits());
+        self.notifier.result(&result, format!("Saved {} limits of group {}", self.form.edit_count(), group.name));
+        if result.is_ok() {
+            self.reload();
+        } else {
+            self.form.browse();
+        }
+    }
+
+    // An empty `group` takes the torrents out of their group
+    fn assign(&mut self, group: &str) {
+        if self.tor_ids.is_empty() {
+            self.notifier.toast(PopupLevel::Warning, "No torrent selected in Home");
+            return;
+        }
+        let result = self.transmission_service.torrent_set_group(&self.tor_ids, group);
+        if group.is_empty() {
+            self.notifier.result(&result, format!("Removed {} torrents from their group", self.tor_ids.len()));
+        } else {
+            self.notifier.result(&result, format!("Assigned {} torrents to group {}", self.tor_ids.len(), group));
+        }
+        self.poller.refresh();
+    }
+
+    fn limit(is_enabled: bool, limit: i64) -> String {
+        if is_enabled { format!("{} kB/s", limit) } else { "unlimited".to_string() }
+    }
+
+    fn table(groups: &[TransmissionGroup], member_counts: &HashMap<&str, usize>) -> Table<'static> {
+        let rows = groups.iter().map(|group| {
+            let session_limits = if group.honors_session_limits { "honored".green() } else { "ignored".yellow() };
+            Row::new(vec![
+                Cell::from(group.name.clone()),
+                Cell::from(Self::limit(group.speed_limit_down_enabled, group.speed_limit_down)),
+                Cell::from(Self::limit(group.speed_limit_up_enabled, group.speed_limit_up)),
+                Cell::from(session_limits),
+                Cell::from(member_counts.get(group.name.as_str()).copied().unwrap_or_default().to_string()),
+            ])
+        });
+        let header = ["Group", "Download", "Upload", "Session limits", "Torrents"]
+            .into_iter()
+            .map(Cell::from)
+            .collect::<Row>()
+            .height(1)
+            .bg(Color::Indexed(236)) // https://en.wikipedia.org/wiki/ANSI_escape_code#Colors
+            .fg(Color::Indexed(255));
+        let selected_row_style = Style::default()
+            .add_modifier(Modifier::REVERSED)
+            .fg(Color::Indexed(255)) // https://en.wikipedia.org/wiki/ANSI_escape_code#Colors
+            .bg(Color::Black);
+        Table::new(
+            rows,
+            [
+                Constraint::Fill(1),
+                Constraint::Length(14),
+                Constraint::Length(14),
+                Constraint::Length(16),
+                Constraint::Length(10),
+            ],
+        )
+        .header(header)
+        .row_highlight_style(selected_row_style)
+    }
+
+    fn render_members(&self, frame: &mut Frame, area: Rect, torrents: &[TransmissionTorrent]) {
+        let Some(group) = self.active_group() else {
+            return;
+        };
+        let mut lines: Vec<Line> = torrents.iter()
+            .filter(|torrent| torrent.group == group.name)
+            .map(|torrent| Line::from(format!("{:>5}  {}", torrent.id, torrent.name)))
+            .collect();
+        if lines.is_empty() {
+            lines.push(Line::from("none".dim()));
+        }
+        let [title_area, list_area] = Layout::vertical([Constraint::Length(1), Constraint::Fill(1)]).areas(area);
+        frame.render_widget(Line::from(format!("Torrents in {}", group.name).bold()), title_area);
+        frame.render_widget(Paragraph::new(Util::cap_lines(lines, list_area.height as usize)), list_area);
+    }
+
+    fn render_input(&self, frame: &mut Frame, area: Rect) {
+        let label = "New group: ";
+        let width = area.width.saturating_sub(label.len() as u16 + 1);
+        let scroll = self.name_input.visual_scroll(width as usize);
+        let value: String = self.name_input.value().chars().skip(scroll).collect();
+        let x = self.name_input.visual_cursor().max(scroll) - scroll;
+        frame.set_cursor_position((area.x + label.len() as u16 + x as u16, area.y));
+        frame.render_widget(Line::from(vec![label.bold(), value.into()]), area);
+    }
+}
+
+impl Renderable<EmptyRenderableArgs> for GroupsScreen {
+    fn render(&mut self, frame: &mut Frame, _args: EmptyRenderableArgs) {
+        let mut key_bindings_block = KeyBindingsBlock::new(self.config_key_bindings.clone());
+        let (title, key_bindings) = match self.mode {
+            Mode::Edit => {
+                let mut title = format!(" Group {} ", self.active_group().map(|group| group.name).unwrap_or_default());
+                if self.form.edit_count() > 0 {
+                    title = format!("{}({} changed) ", title, self.form.edit_count());
+                }
+                let mut key_bindings = self.form.key_bindings();
+                if !self.form.is_previewing() {
+                    key_bindings.push(KeyBindingsBlock::kb_cancel());
+                }
+                (title, key_bindings)
+            }
+            Mode::Name => (" Bandwidth groups ".to_string(), vec![KeyBindingsBlock::kb_confirm(), KeyBindingsBlock::kb_cancel()]),
+            Mode::Browse => {
+                let title = match self.tor_ids.len() {
+                    0 => " Bandwidth groups ".to_string(),
+                    count => format!(" Bandwidth groups ({} torrents selected in Home) ", count)
+                };
+                (title, vec![
+                    key_bindings_block.cnf_kb_home(),
+                    KeyBindingsBlock::kb_new_group(),
+                    KeyBindingsBlock::kb_edit(),
+                    KeyBindingsBlock::kb_assign(),
+                    KeyBindingsBlock::kb_unassign(),
+                    KeyBindingsBlock::kb_refresh(),
+                    KeyBindingsBlock::kb_cancel(),
+                    key_bindings_block.cnf_kb_quit()
+                ])
+            }
+        };
+        let block = Block::bordered()
+            .title(Line::from(title.bold()).centered())
+            .title_bottom(KeyBindingsBlock::key_bindings_as_line(&key_bindings).centered())
+            .padding(Padding::proportional(1))
+            .border_set(border::THICK);
+        let area = block.inner(frame.area());
+        frame.render_widget(block, frame.area());
+
+        let groups = match &self.groups {
+            Ok(groups) => groups.clone(),
+            Err(e) => {
+                frame.render_widget(Paragraph::new(e.to_string().red()).centered(), area);
+                return;
+            }
+        };
+        if self.mode == Mode::Edit {
+            self.form.render(frame, area);
+            return;
+        }
+
+        let input_height = if self.mode == Mode::Name { 2 } else { 0 };
+        let [input_area, table_area, members_area] = Layout::vertical([
+            Constraint::Length(input_height),
+            Constraint::Fill(1),
+            Constraint::Length(MEMBERS_HEIGHT)
+        ]).areas(area);
+        if self.mode == Mode::Name {
+            self.render_input(frame, input_area);
+        }
+        if groups.is_empty() {
+            frame.render_widget(Paragraph::new("No bandwidth group yet".dim()).centered(), table_area);
+            return;
+        }
+        let torrents = self.torrent_store.torrents();
+        let table = Self::table(&groups, &Self::member_counts(&torrents));
+        frame.render_stateful_widget(table, table_area, &mut self.table_state);
+        self.render_members(frame, members_area, &torrents);
+    }
+}
+
+impl KeyEventHandler for GroupsScreen {
+    /*
+       Returns false if we are done from this screen
+    */
+    fn handle_key_event(&mut self, key_event: KeyEvent, event: Event) -> bool {
+        if self.mode == Mode::Edit {
+            match self.form.handle_key_event(key_event, event) {
+                FormAction::Apply => self.apply(),
+                FormAction::Reload => self.edit(),
+                FormAction::Leave => self.mode = Mode::Browse,
+                FormAction::None => {}
+            }
+            return true;
+        }
+        if key_event.kind != KeyEventKind::Press {
+            return true;
+        }
+        match (self.mode, key_event.code) {
+            (Mode::Name, KeyCode::Enter) => self.create(),
+            (Mode::Name, KeyCode::Esc) => self.mode = Mode::Browse,
+            // let input handle it
+            (Mode::Name, _) => {
+                self.name_input.handle_event(&event);
+            }
+            (_, KeyCode::Char('j') | KeyCode::Down) => self.next_row(),
+            (_, KeyCode::Char('k') | KeyCode::Up) => self.previous_row(),
+            (_, KeyCode::Char('n')) => {
+                self.name_input.reset();
+                self.mode = Mode::Name;
+            }
+            (_, KeyCode::Enter) => self.edit(),
+            (_, KeyCode::Char('a')) => {
+                if let Some(group) = self.active_group() {
+                    self.assign(&group.name);
+                }
+            }
+            (_, KeyCode::Char('u')) => self.assign(""),
+            (_, KeyCode::Char('r')) => self.reload(),
+            // leave
+            (_, KeyCode::Esc) => return false,
+            _ => {}
+        }
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn groups() -> Vec<TransmissionGroup> {
+        vec![TransmissionGroup { name: "slow".to_string(), ..Default::default() }]
+    }
+
+    #[test]
+    fn validate_name_trims_the_name() {
+        assert_eq!(GroupsScreen::validate_name("  fast ", &groups()), Ok("fast".to_string()));
+    }
+
+    #[test]
+    fn validate_name_rejects_blank_names() {
+        assert_eq!(GroupsScreen::validate_name("", &groups()), Err("Expected a name".to_string()));
+        assert_eq!(GroupsScreen::validate_name("   ", &[]), Err("Expected a name".to_string()));
+    }
+
+    #[test]
+    fn validate_name_rejects_existing_groups() {
+        assert_eq!(GroupsScreen::validate_name(" slow", &groups()), Err("Group slow already exists".to_string()));
+    }
+
+    #[test]
+    fn member_counts_count_the_torrents_of_each_group() {
+        let torrent = |group: &str| TransmissionTorrent { group: group.to_string(), ..Default::default() };
+        let torrents = vec![torrent("slow"), torrent(""), torrent("slow"), torrent("fast")];
+        let counts = GroupsScreen::member_counts(&torrents);
+        assert_eq!(counts.get("slow"), Some(&2));
+        assert_eq!(counts.get("fast"), Some(&1));
+        assert_eq!(counts.get("other"), None);
+    }
+}
@@ -1,49 +1,204 @@
-use std::collections::HashMap;
+use std::cmp::Ordering;
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::sync::Arc;
 use crate::app::{EmptyRenderableArgs, KeyEventHandler, Renderable, RenderableArgs, Screen};
-use crate::config::{Config, ConfigKeyBindingKey};
+use crate::config::{Config, ConfigKeyBindingKey, SortColumn, SortValues};
 use crate::screen::key_bindings_block::{KeyBindingItem, KeyBindingsBlock};
 use crate::util::Util;
 use chrono::{DateTime, Local, Utc};
 use crossterm::event::{Event, KeyCode, KeyEvent, KeyEventKind, KeyModifiers};
 use ratatui::Frame;
-use ratatui::layout::Constraint;
+use ratatui::layout::{Constraint, Layout, Rect};
 use ratatui::prelude::{Modifier, Style, Stylize, Text};
 use ratatui::style::Color;
 use ratatui::symbols::border;
 use ratatui::text::{Line, StyledGrapheme};
-use ratatui::widgets::{Block, Cell, Padding, Paragraph, Row, Table, TableState};
+use ratatui::widgets::{Block, Cell, Padding, Paragraph, Row, Table, TableState, Tabs};
+use tui_input::Input;
+use tui_input::backend::crossterm::EventHandler;
 use std::fmt::Debug;
 use std::time::{Duration, UNIX_EPOCH};
 use crate::config::ConfigKeyBindingKey::{KbDel, KbOpen};
-use crate::dto::transmission_dto::TransmissionTorrent;
+use crate::dto::transmission_dto::{TransmissionResponse, TransmissionTorrent};
+use crate::error::{RustorError, RustorResult};
+use crate::poller::Poller;
+use crate::screen::popup_screen::{Notifier, PopupLevel};
 use crate::service::transmission_service::TransmissionService;
+use crate::rate_history::RateHistory;
+use crate::screen::rate_graph::RateGraph;
+use crate::store::TorrentStore;
+
+// Header of every column and what it sorts by
+const COLUMNS: [(&str, SortColumn); 9] = [
+    ("Id", SortColumn::Id),
+    ("Name", SortColumn::Name),
+    ("ETA", SortColumn::Eta),
+    ("Done", SortColumn::Done),
+    ("Download", SortColumn::Download),
+    ("Upload", SortColumn::Upload),
+    ("Size", SortColumn::Size),
+    ("Downloaded", SortColumn::Downloaded),
+    ("Added On", SortColumn::AddedOn),
+];
+
+// Status tabs above the table, see `status` in https://github.com/transmission/transmission/blob/main/docs/rpc-spec.md
+#[derive(Default, Clone, Copy, PartialEq)]
+enum StatusFilter {
+    #[default]
+    All,
+    Downloading,
+    Seeding,
+    Paused,
+    Errored,
+    Checking,
+    Queued
+}
+
+impl StatusFilter {
+
+    const ALL: [StatusFilter; 7] = [
+        StatusFilter::All,
+        StatusFilter::Downloading,
+        StatusFilter::Seeding,
+        StatusFilter::Paused,
+        StatusFilter::Errored,
+        StatusFilter::Checking,
+        StatusFilter::Queued
+    ];
+
+    fn title(&self) -> &'static str {
+        match self {
+            StatusFilter::All => "All",
+            StatusFilter::Downloading => "Downloading",
+            StatusFilter::Seeding => "Seeding",
+            StatusFilter::Paused => "Paused",
+            StatusFilter::Errored => "Errored",
+            StatusFilter::Checking => "Checking",
+            StatusFilter::Queued => "Queued"
+        }
+    }
+
+    fn matches(&self, torrent: &TransmissionTorrent) -> bool {
+        match self {
+            StatusFilter::All => true,
+            StatusFilter::Downloading => torrent.status == 4,
+            StatusFilter::Seeding => torrent.status == 6,
+            StatusFilter::Paused => torrent.status == 0,
+            StatusFilter::Errored => torrent.error != 0,
+            StatusFilter::Checking => torrent.is_checking(),
+            StatusFilter::Queued => torrent.status == 3 || torrent.status == 5
+        }
+    }
+
+    fn index(&self) -> usize {
+        Self::ALL.iter().position(|filter| filter == self).unwrap_or(0)
+    }
+
+    fn next(&self) -> Self {
+        Self::ALL[(self.index() + 1) % Self::ALL.len()]
+    }
+
+    fn previous(&self) -> Self {
+        Self::ALL[(self.index() + Self::ALL.len() - 1) % Self::ALL.len()]
+    }
+}
+
+// What the one line input below the status tabs is currently editing
+#[derive(Default, Clone, Copy, PartialEq)]
+enum InputMode {
+    #[default]
+    None,
+    Filter,
+    Labels,
+    Rename
+}
+
+// One line of the table, grouping adds a heading above the torrents of every label
+#[derive(Clone, PartialEq)]
+enum HomeRow {
+    // `None` for the torrents without any label
+    Group(Option<String>),
+    Torrent(i64)
+}
+
+// Everything the rows are built from, they are only built again once any of it changes
+#[derive(Clone, PartialEq)]
+struct RowsKey {
+    generation: u64,
+    status_filter: StatusFilter,
+    filter: String,
+    sort: SortValues,
+    is_grouped: bool,
+    collapsed_groups: BTreeSet<Option<String>>
+}
 
 #[derive(Default, Clone)]
 struct State {
-    torrent_ids: Vec<i64>,
-    row_index_last_used_for_fetching_torrent: usize,
-    selected_row_torrent: TransmissionTorrent
+    // what each row shows, as of the last render
+    rows: Vec<HomeRow>,
+    // torrents passing the status tab and the quick-search, in the order they are shown
+    visible: Vec<TransmissionTorrent>,
+    // what `rows` and `visible` were built from, `None` until the first render
+    rows_key: Option<RowsKey>,
+    // set when the last poll failed, the store is then stale
+    error: Option<RustorError>,
+    sort: SortValues,
+    status_filter: StatusFilter,
+    // `/` quick-search over name, labels and tracker hosts
+    filter_input: Input,
+    // comma separated labels for the targeted torrents
+    label_input: Input,
+    // new name of the torrent in the active row
+    rename_input: Input,
+    input_mode: InputMode,
+    // torrents marked for a bulk action
+    marked: BTreeSet<i64>,
+    // rows grouped under label headings (a torrent with several labels shows up under each of them)
+    is_grouped: bool,
+    collapsed_groups: BTreeSet<Option<String>>,
+    // left by the last label completion when it was ambiguous
+    label_matches: Vec<String>,
+    // torrents in the store, before any filtering
+    total: usize
 }
 
 #[derive(Clone)]
 pub struct HomeScreen {
     config_key_bindings: HashMap<ConfigKeyBindingKey, char>,
+    transmission_service: Arc<TransmissionService>,
+    notifier: Notifier,
+    poller: Poller,
+    torrent_store: TorrentStore,
+    rate_history: RateHistory,
     table_state: TableState,
     state: State
 }
 
 impl HomeScreen {
 
-    pub fn new(config_key_bindings: HashMap<ConfigKeyBindingKey, char>) -> Self {
+    pub fn new(
+        config_key_bindings: HashMap<ConfigKeyBindingKey, char>,
+        transmission_service: Arc<TransmissionService>,
+        notifier: Notifier,
+        poller: Poller,
+        torrent_store: TorrentStore,
+        rate_history: RateHistory,
+        sort: SortValues
+    ) -> Self {
         Self {
             config_key_bindings,
-            table_state: TableState::default().with_selected(0),
-            state: State::default()
+            transmission_service,
+            notifier,
+            poller,
+            torrent_store,
+            rate_history,
+            table_state: TableState::default().with_selected(0).with_selected_column(0),
+            state: State { sort, ..State::default() }
         }
     }
 
     pub fn next_row(&mut self) {
-        let max_index = self.state.torrent_ids.len() as i32 - 1;
+        let max_index = self.state.rows.len() as i32 - 1;
         let i = match self.table_state.selected() {
             Some(i) => {
                 if i as i32 == max_index {
@@ -73,73 +228,356 @@ impl HomeScreen {
         // self.scroll_state = self.scroll_state.position(i * ITEM_HEIGHT);
     }
 
-    // TODO fix
     pub fn next_column(&mut self) {
-        self.table_state.select_next_column();
+        let i = self.active_column();
+        self.table_state.select_column(Some((i + 1).min(COLUMNS.len() - 1)));
     }
 
-    // TODO fix
     pub fn previous_column(&mut self) {
-        self.table_state.select_previous_column();
+        let i = self.active_column();
+        self.table_state.select_column(Some(i.saturating_sub(1)));
+    }
+
+    fn active_column(&self) -> usize {
+        self.table_state.selected_column().unwrap_or(0)
+    }
+
+    // Sort by the focused column, ascending first and then toggling the direction
+    pub fn sort_by_active_column(&mut self) {
+        let column = COLUMNS[self.active_column()].1;
+        self.state.sort = if self.state.sort.column == column {
+            SortValues { column, descending: !self.state.sort.descending }
+        } else {
+            SortValues { column, descending: false }
+        };
+    }
+
+    pub fn sort(&self) -> SortValues {
+        self.state.sort
+    }
+
+    // Start over (e.g. after connecting to another daemon, whose torrents the `Poller` fetches afresh)
+    pub fn reset(&mut self) {
+        self.state = State { sort: self.state.sort, ..State::default() };
+        self.table_state.select(Some(0));
     }
 
     pub fn active_row(&self) -> usize {
         self.table_state.selected().unwrap_or(0)
     }
 
-    pub fn active_row_torrent(&mut self) -> TransmissionTorrent {
-        let cur_sel_index = self.table_state.selected().unwrap_or(0);
-        if  self.state.selected_row_torrent.id != 0 &&
-            self.state.row_index_last_used_for_fetching_torrent == cur_sel_index {
+    // Outcome of the last poll, the torrents themselves are read from the store when rendering
+    pub fn update(&mut self, torrents: RustorResult<()>) {
+        self.state.error = torrents.err();
+        self.report_verified();
+    }
+
+    // Summary of every verification which is over, wherever it was started
+    fn report_verified(&self) {
+        self.torrent_store.take_verified().into_iter().for_each(|verified| {
+            let corrupt = Util::print_bytes(verified.torrent.corrupt_ever as f64);
+            if verified.missing > 0 {
+                let missing = Util::print_bytes(verified.missing as f64);
+                let msg = format!("Verified {}: {} missing, {} corrupt ever", verified.torrent.name, missing, corrupt);
+                self.notifier.toast(PopupLevel::Warning, msg);
+            } else {
+                let msg = format!("Verified {}: nothing missing, {} corrupt ever", verified.torrent.name, corrupt);
+                self.notifier.toast(PopupLevel::Info, msg);
+            }
+        });
+    }
+
+    pub fn active_row_torrent(&self) -> Option<TransmissionTorrent> {
+        self.active_row_torrent_id().and_then(|torrent_id| self.torrent_store.get(torrent_id))
+    }
 
-            return self.state.selected_row_torrent.clone()
+    pub fn active_row_torrent_id(&self) -> Option<i64> {
+        match self.state.rows.get(self.active_row()) {
+            Some(HomeRow::Torrent(torrent_id)) => Some(*torrent_id),
+            _ => None
         }
+    }
+
+    // Torrents in a visible row, once each
+    fn visible_torrent_ids(&self) -> BTreeSet<i64> {
+        self.state.rows.iter().filter_map(|row| match row {
+            HomeRow::Torrent(torrent_id) => Some(*torrent_id),
+            HomeRow::Group(_) => None
+        }).collect()
+    }
+
+    // Torrents an action applies to: the marked ones or else the one in the active row
+    pub fn target_ids(&self) -> Vec<i64> {
+        if self.state.marked.is_empty() {
+            self.active_row_torrent_id().into_iter().collect()
+        } else {
+            self.state.marked.iter().copied().collect()
+        }
+    }
+
+    // On a heading every torrent of the group gets marked, or unmarked when they all are
+    pub fn toggle_mark(&mut self) {
+        if let Some(HomeRow::Group(label)) = self.state.rows.get(self.active_row()) {
+            let torrent_ids = self.group_torrent_ids(label);
+            if torrent_ids.is_subset(&self.state.marked) {
+                self.state.marked.retain(|torrent_id| !torrent_ids.contains(torrent_id));
+            } else {
+                self.state.marked.extend(torrent_ids);
+            }
+        } else if let Some(torrent_id) = self.active_row_torrent_id() && !self.state.marked.remove(&torrent_id) {
+            self.state.marked.insert(torrent_id);
+        }
+    }
+
+    // Mark every visible row (those hidden by a filter are left as they are)
+    pub fn mark_all(&mut self) {
+        self.state.marked.extend(self.visible_torrent_ids());
+    }
 
-        self.state.row_index_last_used_for_fetching_torrent = cur_sel_index.clone();
-        let torrent_id = self.state.torrent_ids[cur_sel_index].to_string();
-        let torrent = TransmissionService::torrent_info(torrent_id)
-            .arguments
-            .torrents[0].clone();
-        self.state.selected_row_torrent = torrent.clone();
-
-        torrent.clone()
-    }
-
-    fn table(self, torrents: &Vec<TransmissionTorrent>) -> Table<'static> {
-        let rows = torrents.iter().enumerate().map(|(i, torrent)| {
-            let item = [
-                &torrent.id.to_string(),
-                &torrent.name,
-                &torrent.eta(),
-                &torrent.percentage_done(),
-                &torrent.download_rate(),
-                &torrent.upload_rate(),
-                &torrent.total_size(),
-                &torrent.downloaded(),
-                &Util::print_epoch(torrent.added_date as u64),
-                &torrent.peers_client_name(),
-            ];
-            // TODO show tor error
-            // TODO show done status
-            item.into_iter()
-                .map(|content| Cell::from(Text::from(format!("\n{content}\n"))))
-                .collect::<Row>()
-                .height(3)
+    // Invert the marks of every visible row
+    pub fn invert_marks(&mut self) {
+        self.visible_torrent_ids().into_iter().for_each(|torrent_id| {
+            if !self.state.marked.remove(&torrent_id) {
+                self.state.marked.insert(torrent_id);
+            }
         });
-        let header = [
-            "Id",
-            "Name",
-            "ETA",
-            "Done",
-            "Download",
-            "Upload",
-            "Size",
-            "Downloaded",
-            "Added On",
-        ]
-        .into_iter()
-        .map(Cell::from)
-        .collect::<Row>()
+    }
+
+    pub fn toggle_grouped(&mut self) {
+        self.state.is_grouped = !self.state.is_grouped;
+    }
+
+    fn toggle_collapsed(&mut self) {
+        if let Some(HomeRow::Group(label)) = self.state.rows.get(self.active_row()).cloned()
+            && !self.state.collapsed_groups.remove(&label) {
+            self.state.collapsed_groups.insert(label);
+        }
+    }
+
+    // Torrents of a group, collapsed or not, among those passing the filters
+    fn group_torrent_ids(&self, label: &Option<String>) -> BTreeSet<i64> {
+        self.state.visible
+            .iter()
+            .filter(|torrent| Self::has_label(torrent, label))
+            .map(|torrent| torrent.id)
+            .collect()
+    }
+
+    fn has_label(torrent: &TransmissionTorrent, label: &Option<String>) -> bool {
+        match label {
+            Some(label) => torrent.labels.contains(label),
+            None => torrent.labels.is_empty()
+        }
+    }
+
+    // Rows in the order shown, with a heading for every label (torrents without any last) when grouped
+    fn rows(&self, torrents: &[TransmissionTorrent]) -> Vec<HomeRow> {
+        if !self.state.is_grouped {
+            return torrents.iter().map(|torrent| HomeRow::Torrent(torrent.id)).collect();
+        }
+        let labels: BTreeSet<String> = torrents.iter().flat_map(|torrent| torrent.labels.iter().cloned()).collect();
+        let mut groups: Vec<Option<String>> = labels.into_iter().map(Some).collect();
+        if torrents.iter().any(|torrent| torrent.labels.is_empty()) {
+            groups.push(None);
+        }
+        groups.into_iter().flat_map(|label| {
+            let mut rows = vec![HomeRow::Group(label.clone())];
+            if !self.state.collapsed_groups.contains(&label) {
+                rows.extend(torrents.iter().filter(|torrent| Self::has_label(torrent, &label)).map(|torrent| HomeRow::Torrent(torrent.id)));
+            }
+            rows
+        }).collect()
+    }
+
+    // Complete the label being typed (the last one) from those used by any torrent
+    fn complete_label(&mut self) {
+        let value = self.state.label_input.value().to_string();
+        let (typed, prefix) = match value.rsplit_once(',') {
+            Some((typed, prefix)) => (format!("{}, ", typed), prefix.trim().to_lowercase()),
+            None => (String::new(), value.trim().to_lowercase())
+        };
+        let matches: Vec<String> = self.torrent_store
+            .labels()
+            .into_iter()
+            .filter(|label| label.to_lowercase().starts_with(&prefix))
+            .collect();
+        let completed = match matches.as_slice() {
+            [] => return,
+            [label] => label.clone(),
+            // as far as all matches agree
+            [first, others @ ..] => others.iter().fold(first.clone(), |common, label| {
+                common.chars().zip(label.chars()).take_while(|(a, b)| a.to_lowercase().eq(b.to_lowercase())).map(|(a, _)| a).collect()
+            })
+        };
+        self.state.label_input = Input::new(format!("{}{}", typed, completed));
+        self.state.label_matches = if matches.len() > 1 { matches } else { vec![] };
+    }
+
+    // Run an action on all targets in one request, returns whether it succeeded
+    fn run_on_targets<F>(&mut self, done: &str, action: F) -> bool
+    where F: Fn(&TransmissionService, &[i64]) -> RustorResult<TransmissionResponse> {
+        let torrent_ids = self.target_ids();
+        let result = match torrent_ids.as_slice() {
+            [] => return false,
+            ids => action(&self.transmission_service, ids)
+        };
+        match torrent_ids.as_slice() {
+            [torrent_id] => self.notifier.result(&result, format!("{} torrent {}", done, torrent_id)),
+            _ => self.notifier.result(&result, format!("{} {} torrents", done, torrent_ids.len()))
+        }
+        self.poller.refresh();
+        result.is_ok()
+    }
+
+    // The outcome is reported once the daemon is done checking, see `report_verified`
+    fn verify(&mut self) {
+        if self.run_on_targets("Verifying", |service, ids| service.torrent_verify(ids)) {
+            self.torrent_store.verify(&self.target_ids());
+        }
+    }
+
+    // Start editing the labels, prefilled with the current ones when a single torrent is targeted
+    fn edit_labels(&mut self) {
+        let labels = match self.target_ids().as_slice() {
+            [torrent_id] => self.torrent_store.get(*torrent_id).map(|torrent| torrent.labels.join(", ")).unwrap_or_default(),
+            _ => String::default()
+        };
+        self.state.label_input = Input::new(labels);
+        self.state.label_matches.clear();
+        self.state.input_mode = InputMode::Labels;
+    }
+
+    fn set_labels(&mut self) {
+        let labels: Vec<String> = self.state.label_input
+            .value()
+            .split(',')
+            .map(|label| label.trim().to_string())
+            .filter(|label| !label.is_empty())
+            .collect();
+        self.run_on_targets("Labeled", |service, ids| service.torrent_set_labels(ids, &labels));
+    }
+
+    /*
+       Percentage done, how far the data has been checked, or for how long the data has been moving (the rpc
+       does not tell how far a move got)
+    */
+    fn done(&self, torrent: &TransmissionTorrent) -> String {
+        match self.torrent_store.relocation(torrent.id) {
+            Some(relocation) if relocation.move_data => {
+                format!("Moving {}", Util::print_duration(relocation.started_on.elapsed().as_secs() as i64))
+            }
+            Some(_) => "Relocating".to_string(),
+            None if torrent.status == 1 => "Check queued".to_string(),
+            None if torrent.is_checking() => {
+                format!("{} {:.0} %", Util::print_gauge(torrent.recheck_progress, 8), 100f64 * torrent.recheck_progress)
+            }
+            None => torrent.percentage_done()
+        }
+    }
+
+    // Start renaming the torrent in the active row, prefilled with its name
+    fn edit_name(&mut self) {
+        if let Some(torrent) = self.active_row_torrent() {
+            self.state.rename_input = Input::new(torrent.name);
+            self.state.input_mode = InputMode::Rename;
+        }
+    }
+
+    fn rename(&mut self) {
+        let Some(torrent) = self.active_row_torrent() else {
+            return;
+        };
+        let name = match Util::validate_file_name(self.state.rename_input.value()) {
+            Ok(name) if name == torrent.name => return,
+            Ok(name) => name,
+            Err(e) => {
+                self.notifier.toast(PopupLevel::Error, e);
+                return;
+            }
+        };
+        let result = self.transmission_service.torrent_rename_path(torrent.id, &torrent.name, &name);
+        self.notifier.result(&result, format!("Renamed to {}", name));
+        if result.is_ok() {
+            self.torrent_store.rename_path(torrent.id, &torrent.name, &name);
+        }
+        self.poller.refresh();
+    }
+
+    fn torrent_row(&self, torrent: &TransmissionTorrent) -> Row<'static> {
+        let item = [
+            &torrent.id.to_string(),
+            &torrent.name,
+            &torrent.eta(),
+            &self.done(torrent),
+            &torrent.download_rate(),
+            &torrent.upload_rate(),
+            &torrent.total_size(),
+            &torrent.downloaded(),
+            &Util::print_epoch(torrent.added_date as u64),
+            &torrent.peers_client_name(),
+        ];
+        // TODO show tor error
+        // TODO show done status
+        let is_marked = self.state.marked.contains(&torrent.id);
+        let row = item.into_iter()
+            .enumerate()
+            .map(|(column, content)| match column {
+                0 if is_marked => Cell::from(Text::from(format!("\n* {content}\n"))),
+                // labels below the name
+                1 if !torrent.labels.is_empty() => Cell::from(Text::from(vec![
+                    Line::from(""),
+                    Line::from(content.to_string()),
+                    Line::from(torrent.labels.join(", ")).dim()
+                ])),
+                _ => Cell::from(Text::from(format!("\n{content}\n")))
+            })
+            .collect::<Row>()
+            .height(3);
+        if is_marked { row.fg(Color::Yellow) } else { row }
+    }
+
+    // Heading of a group with the totals of its torrents (collapsed or not)
+    fn group_row(&self, label: &Option<String>, torrents: &[TransmissionTorrent]) -> Row<'static> {
+        let group: Vec<&TransmissionTorrent> = torrents.iter().filter(|torrent| Self::has_label(torrent, label)).collect();
+        let sum = |value: fn(&TransmissionTorrent) -> i64| group.iter().map(|torrent| value(torrent)).sum::<i64>();
+        let (size, downloaded) = (sum(|torrent| torrent.size_when_done), sum(|torrent| torrent.size_when_done - torrent.left_until_done));
+        let done = if size > 0 { 100f64 * downloaded as f64 / size as f64 } else { 100f64 };
+        let icon = if self.state.collapsed_groups.contains(label) { "\u{25b8}" } else { "\u{25be}" };
+        let name = label.clone().unwrap_or("(no label)".to_string());
+        Row::new(vec![
+            Cell::from(""),
+            Cell::from(format!("{} {} ({})", icon, name, group.len())),
+            Cell::from(""),
+            Cell::from(format!("{:.2} %", done)),
+            Cell::from(format!("\u{2193} {}/s", Util::print_bytes(sum(|torrent| torrent.rate_download) as f64))),
+            Cell::from(format!("\u{2191} {}/s", Util::print_bytes(sum(|torrent| torrent.rate_upload) as f64))),
+            Cell::from(Util::print_bytes(size as f64)),
+            Cell::from(Util::print_bytes(downloaded as f64)),
+        ]).bold().fg(Color::Cyan)
+    }
+
+    fn table(&self, torrents: &[TransmissionTorrent]) -> Table<'static> {
+        let by_id: HashMap<i64, &TransmissionTorrent> = torrents.iter().map(|torrent| (torrent.id, torrent)).collect();
+        let rows: Vec<Row> = self.state.rows.iter().filter_map(|row| match row {
+            HomeRow::Group(label) => Some(self.group_row(label, torrents)),
+            HomeRow::Torrent(torrent_id) => by_id.get(torrent_id).map(|torrent| self.torrent_row(torrent))
+        }).collect();
+        let header = COLUMNS
+            .iter()
+            .enumerate()
+            .map(|(i, (title, column))| {
+                let mut title = title.to_string();
+                if *column == self.state.sort.column {
+                    title = format!("{title} {}", if self.state.sort.descending { '\u{2193}' } else { '\u{2191}' });
+                }
+                // column cursor
+                if i == self.active_column() {
+                    Cell::from(title.underlined().bold())
+                } else {
+                    Cell::from(title)
+                }
+            })
+            .collect::<Row>()
         .height(1)
         .bg(Color::Indexed(236)) // https://en.wikipedia.org/wiki/ANSI_escape_code#Colors
         .fg(Color::Indexed(255));
@@ -151,8 +589,8 @@ impl HomeScreen {
             rows,
             [
                 // + 1 is for padding.
-                Constraint::Length(4),
-                Constraint::Length(self.name_len(&torrents) + 1),
+                Constraint::Length(6),
+                Constraint::Length(self.name_len(torrents) + 1),
                 Constraint::Length(16),
                 Constraint::Length(16),
                 Constraint::Length(16),
@@ -166,20 +604,112 @@ impl HomeScreen {
         .row_highlight_style(selected_row_style)
     }
 
-    // Find for name column which row has the largest (this is done only for string values which might be too long)
-    fn name_len(&self, items: &Vec<TransmissionTorrent>) -> u16 {
-        items
-            .iter()
-            .map(|t| t.name.chars().count())
-            .max()
-            .unwrap_or(0) as u16
+    fn matches_filter(&self, torrent: &TransmissionTorrent) -> bool {
+        let filter = self.state.filter_input.value().to_lowercase();
+        if filter.is_empty() {
+            return true;
+        }
+        torrent.name.to_lowercase().contains(&filter)
+            || torrent.labels.iter().any(|label| label.to_lowercase().contains(&filter))
+            || torrent.tracker_hosts().iter().any(|host| host.to_lowercase().contains(&filter))
     }
 
-    // Find for combined peers.client_name column which row has the largest (this is done only for string values which might be too long)
-    fn peer_client_name_len(&self, items: &Vec<TransmissionTorrent>) -> u16 {
+    /*
+        Filter and sort the torrents of the store again, unless neither the store nor the filters, the sort or the
+        groups changed since the last time. The selection follows its torrent (or heading) when rows move around.
+    */
+    fn refresh_rows(&mut self) {
+        let rows_key = RowsKey {
+            generation: self.torrent_store.generation(),
+            status_filter: self.state.status_filter,
+            filter: self.state.filter_input.value().to_string(),
+            sort: self.state.sort,
+            is_grouped: self.state.is_grouped,
+            collapsed_groups: self.state.collapsed_groups.clone()
+        };
+        if self.state.rows_key.as_ref() == Some(&rows_key) {
+            return;
+        }
+        let mut torrents: Vec<TransmissionTorrent> = self.torrent_store
+            .torrents()
+            .into_iter()
+            .filter(|torrent| self.state.status_filter.matches(torrent) && self.matches_filter(torrent))
+            .collect();
+        Self::sort_torrents(&mut torrents, self.state.sort);
+
+        let selected_row = self.state.rows.get(self.active_row()).cloned();
+        self.state.rows = self.rows(&torrents);
+        match self.state.rows.iter().position(|row| Some(row) == selected_row.as_ref()) {
+            Some(i) => self.table_state.select(Some(i)),
+            None if self.active_row() >= self.state.rows.len() => self.table_state.select(Some(self.state.rows.len().saturating_sub(1))),
+            None => {}
+        }
+        self.state.visible = torrents;
+        self.state.total = self.torrent_store.count();
+        self.state.rows_key = Some(rows_key);
+    }
+
+    fn tabs(&self) -> Tabs<'static> {
+        Tabs::new(StatusFilter::ALL.iter().map(|filter| filter.title()))
+            .select(self.state.status_filter.index())
+            .highlight_style(Style::default().add_modifier(Modifier::REVERSED))
+    }
+
+    // One line input, either the quick-search or the labels being edited
+    fn render_input(&self, frame: &mut Frame, area: Rect) {
+        let (label, input) = match self.state.input_mode {
+            InputMode::Labels => ("Labels: ", &self.state.label_input),
+            InputMode::Rename => ("Rename: ", &self.state.rename_input),
+            _ => ("Filter: ", &self.state.filter_input)
+        };
+        let width = area.width.saturating_sub(label.len() as u16 + 1);
+        let scroll = input.visual_scroll(width as usize);
+        let value: String = input.value().chars().skip(scroll).collect();
+        let mut line = Line::from(vec![label.bold(), value.into()]);
+        if self.state.input_mode == InputMode::Labels && !self.state.label_matches.is_empty() {
+            line.push_span(format!("  [{}]", self.state.label_matches.join(", ")).dim());
+        }
+        frame.render_widget(line, area);
+        if self.state.input_mode != InputMode::None {
+            let x = input.visual_cursor().max(scroll) - scroll;
+            frame.set_cursor_position((area.x + label.len() as u16 + x as u16, area.y));
+        }
+    }
+
+    // Ties (and the `Id` column) fall back to the id so that rows do not jump between refreshes
+    fn sort_torrents(torrents: &mut [TransmissionTorrent], sort: SortValues) {
+        torrents.sort_by(|a, b| {
+            let ordering = match sort.column {
+                SortColumn::Id => Ordering::Equal,
+                SortColumn::Name => a.name.to_lowercase().cmp(&b.name.to_lowercase()),
+                SortColumn::Eta => Self::eta_key(a).cmp(&Self::eta_key(b)),
+                SortColumn::Done => a.calc_ratio().total_cmp(&b.calc_ratio()),
+                SortColumn::Download => a.rate_download.cmp(&b.rate_download),
+                SortColumn::Upload => a.rate_upload.cmp(&b.rate_upload),
+                SortColumn::Size => a.size_when_done.cmp(&b.size_when_done),
+                SortColumn::Downloaded => (a.size_when_done - a.left_until_done).cmp(&(b.size_when_done - b.left_until_done)),
+                SortColumn::AddedOn => a.added_date.cmp(&b.added_date)
+            }.then(a.id.cmp(&b.id));
+            if sort.descending { ordering.reverse() } else { ordering }
+        });
+    }
+
+    // Done torrents first, unknown ETA last
+    fn eta_key(torrent: &TransmissionTorrent) -> i64 {
+        if torrent.left_until_done == 0 {
+            -1
+        } else if torrent.eta <= 0 {
+            i64::MAX
+        } else {
+            torrent.eta
+        }
+    }
+
+    // Find for name column which row has the largest (this is done only for string values which might be too long)
+    fn name_len(&self, items: &[TransmissionTorrent]) -> u16 {
         items
             .iter()
-            .map(|torrent| torrent.peers_client_name().chars().count())
+            .map(|t| t.name.chars().count())
             .max()
             .unwrap_or(0) as u16
     }
@@ -187,12 +717,17 @@ impl HomeScreen {
 
 impl Renderable<EmptyRenderableArgs> for HomeScreen {
     fn render(&mut self, frame: &mut Frame, args: EmptyRenderableArgs) {
-        let torrents: Vec<TransmissionTorrent> = TransmissionService::torrent_list()
-            .arguments
-            .torrents;
-        self.state.torrent_ids.extend(torrents.iter().map(|t| t.id));
+        self.refresh_rows();
 
-        let title = Line::from(" All torrents ".bold());
+        // marks of removed torrents go away with them
+        let torrent_store = self.torrent_store.clone();
+        self.state.marked.retain(|torrent_id| torrent_store.get(*torrent_id).is_some());
+
+        let mut title = format!(" {} of {} torrents ", self.state.visible.len(), self.state.total);
+        if !self.state.marked.is_empty() {
+            title = format!("{}({} marked) ", title, self.state.marked.len());
+        }
+        let title = Line::from(title.bold());
         let mut key_bindings_block = KeyBindingsBlock::new(self.config_key_bindings.clone());
         let key_bindings = vec![
             key_bindings_block.cnf_kb_add(),
@@ -201,6 +736,19 @@ impl Renderable<EmptyRenderableArgs> for HomeScreen {
             key_bindings_block.cnf_kb_info(),
             key_bindings_block.cnf_kb_open(),
             key_bindings_block.cnf_kb_reann(),
+            key_bindings_block.cnf_kb_limits(),
+            key_bindings_block.cnf_kb_move(),
+            KeyBindingsBlock::kb_mark(),
+            KeyBindingsBlock::kb_filter(),
+            KeyBindingsBlock::kb_status(),
+            KeyBindingsBlock::kb_sort(),
+            KeyBindingsBlock::kb_group(),
+            KeyBindingsBlock::kb_faster(),
+            KeyBindingsBlock::kb_slower(),
+            key_bindings_block.cnf_kb_stats(),
+            key_bindings_block.cnf_kb_settings(),
+            key_bindings_block.cnf_kb_groups(),
+            key_bindings_block.cnf_kb_profiles(),
             key_bindings_block.cnf_kb_help(),
             key_bindings_block.cnf_kb_quit()
         ];
@@ -210,9 +758,31 @@ impl Renderable<EmptyRenderableArgs> for HomeScreen {
             .title_bottom(bottom_line.centered())
             .padding(Padding::proportional(1))
             .border_set(border::THICK);
-        let table = self.clone().table(&torrents).block(block);
+        let inner_area = block.inner(frame.area());
+        frame.render_widget(block, frame.area());
+
+        let input_height = if self.state.input_mode != InputMode::None || !self.state.filter_input.value().is_empty() { 1 } else { 0 };
+        let [tabs_area, input_area, table_area, rates_area] = Layout::vertical([
+            Constraint::Length(2),
+            Constraint::Length(input_height),
+            Constraint::Fill(1),
+            Constraint::Length(3)
+        ]).areas(inner_area);
+        frame.render_widget(self.tabs(), tabs_area);
+        self.render_input(frame, input_area);
 
-        frame.render_stateful_widget(table, frame.area(), &mut self.table_state);
+        match &self.state.error {
+            None => {
+                let table = self.table(&self.state.visible);
+                frame.render_stateful_widget(table, table_area, &mut self.table_state);
+            }
+            Some(e) => {
+                let error = Paragraph::new(e.to_string().red()).centered();
+                frame.render_widget(error, table_area);
+            }
+        }
+        // footer with the rates of all torrents together
+        frame.render_widget(RateGraph::new(self.rate_history.global()), rates_area);
     }
 }
 
@@ -220,45 +790,148 @@ impl KeyEventHandler for HomeScreen {
     fn handle_key_event(&mut self, key_event: KeyEvent, event: Event) -> bool {
         let ctrl = key_event.modifiers.contains(KeyModifiers::CONTROL);
         let shft = key_event.modifiers.contains(KeyModifiers::SHIFT);
+        if key_event.kind == KeyEventKind::Press && self.state.input_mode != InputMode::None && !ctrl {
+            match (self.state.input_mode, key_event.code) {
+                // keep the filter
+                (InputMode::Filter, KeyCode::Enter) => self.state.input_mode = InputMode::None,
+                // drop the filter
+                (InputMode::Filter, KeyCode::Esc) => {
+                    self.state.filter_input.reset();
+                    self.state.input_mode = InputMode::None;
+                }
+                (InputMode::Labels, KeyCode::Enter) => {
+                    self.set_labels();
+                    self.state.input_mode = InputMode::None;
+                }
+                (InputMode::Labels, KeyCode::Esc) => self.state.input_mode = InputMode::None,
+                (InputMode::Labels, KeyCode::Tab) => self.complete_label(),
+                // let input handle it
+                (InputMode::Labels, _) => {
+                    self.state.label_input.handle_event(&event);
+                    self.state.label_matches.clear();
+                }
+                (InputMode::Rename, KeyCode::Enter) => {
+                    self.rename();
+                    self.state.input_mode = InputMode::None;
+                }
+                (InputMode::Rename, KeyCode::Esc) => self.state.input_mode = InputMode::None,
+                (InputMode::Rename, _) => {
+                    self.state.rename_input.handle_event(&event);
+                }
+                _ => {
+                    self.state.filter_input.handle_event(&event);
+                }
+            }
+            return false;
+        }
         if key_event.kind == KeyEventKind::Press {
             match key_event.code {
+                // first, so that Ctrl+<key> never falls into the plain key arms below
+                KeyCode::Char(c) if ctrl => {
+                    if c == *self.config_key_bindings.get(&KbOpen).unwrap() && let Some(torrent_id) = self.active_row_torrent_id() {
+                        let result = self.transmission_service
+                            .torrent_info(torrent_id.to_string())
+                            .and_then(|response| match response.arguments.torrents.first() {
+                                Some(torrent) => self.transmission_service.torrent_location(torrent),
+                                None => Ok(())
+                            });
+                        if let Err(e) = result {
+                            self.notifier.modal(PopupLevel::Error, e.to_string());
+                        }
+                    }
+                    false
+                }
                 KeyCode::Char('j') | KeyCode::Down => {
                     self.next_row();
                     false
                 }
+                KeyCode::Char('/') => {
+                    self.state.input_mode = InputMode::Filter;
+                    false
+                }
+                // drop the filter, then the marks
+                KeyCode::Esc => {
+                    if self.state.filter_input.value().is_empty() {
+                        self.state.marked.clear();
+                    }
+                    self.state.filter_input.reset();
+                    false
+                }
+                // marking
+                KeyCode::Char(' ') => {
+                    self.toggle_mark();
+                    self.next_row();
+                    false
+                }
+                KeyCode::Char('a') => {
+                    self.mark_all();
+                    false
+                }
+                KeyCode::Char('i') => {
+                    self.invert_marks();
+                    false
+                }
+                KeyCode::Tab => {
+                    self.state.status_filter = self.state.status_filter.next();
+                    false
+                }
+                KeyCode::BackTab => {
+                    self.state.status_filter = self.state.status_filter.previous();
+                    false
+                }
                 KeyCode::Char('k') | KeyCode::Up => {
                     self.previous_row();
                     false
                 }
-                // TODO fix
                 KeyCode::Char('l') | KeyCode::Right => {
                     self.next_column();
                     false
                 }
-                // TODO fix
                 KeyCode::Char('h') | KeyCode::Left => {
                     self.previous_column();
                     false
                 }
-                KeyCode::Char('s') => {
+                KeyCode::Char('o') => {
+                    self.sort_by_active_column();
+                    false
+                }
+                KeyCode::Char('s') | KeyCode::Char('S') => {
                     if shft {
-                        let cur_sel_indx = self.active_row();
-                        TransmissionService::torrent_stop(self.state.torrent_ids[cur_sel_indx].to_string());
-                        false
+                        self.run_on_targets("Stopped", |service, ids| service.torrent_stop(ids));
                     } else {
-                        let cur_sel_indx = self.table_state.selected().unwrap();
-                        TransmissionService::torrent_start(self.state.torrent_ids[cur_sel_indx].to_string());
-                        false
+                        self.run_on_targets("Started", |service, ids| service.torrent_start(ids));
                     }
+                    false
                 }
-                KeyCode::Char(c) if ctrl => {
-                    if c == *self.config_key_bindings.get(&KbOpen).unwrap() {
-                        let cur_sel_index = self.table_state.selected().unwrap();
-                        let torrent = &TransmissionService::torrent_info(self.state.torrent_ids[cur_sel_index].to_string())
-                            .arguments
-                            .torrents[0];
-                        TransmissionService::torrent_location(&torrent);
-                    }
+                KeyCode::Char('v') => {
+                    self.verify();
+                    false
+                }
+                KeyCode::Char('L') => {
+                    self.edit_labels();
+                    false
+                }
+                KeyCode::Char('r') => {
+                    self.edit_name();
+                    false
+                }
+                KeyCode::Char('g') => {
+                    self.toggle_grouped();
+                    false
+                }
+                KeyCode::Enter => {
+                    self.toggle_collapsed();
+                    false
+                }
+                // refresh rate
+                KeyCode::Char('+') => {
+                    let interval_ms = self.poller.faster();
+                    self.notifier.toast(PopupLevel::Info, format!("Refreshing every {} ms", interval_ms));
+                    false
+                }
+                KeyCode::Char('-') => {
+                    let interval_ms = self.poller.slower();
+                    self.notifier.toast(PopupLevel::Info, format!("Refreshing every {} ms", interval_ms));
                     false
                 }
                 _ => true,
@@ -268,3 +941,73 @@ impl KeyEventHandler for HomeScreen {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn torrent(id: i64, name: &str) -> TransmissionTorrent {
+        TransmissionTorrent { id, name: name.to_string(), size_when_done: 100, left_until_done: 100, ..Default::default() }
+    }
+
+    fn sorted_ids(mut torrents: Vec<TransmissionTorrent>, column: SortColumn, descending: bool) -> Vec<i64> {
+        HomeScreen::sort_torrents(&mut torrents, SortValues { column, descending });
+        torrents.iter().map(|torrent| torrent.id).collect()
+    }
+
+    #[test]
+    fn sorts_by_id() {
+        let torrents = vec![torrent(3, "c"), torrent(1, "a"), torrent(2, "b")];
+        assert_eq!(sorted_ids(torrents.clone(), SortColumn::Id, false), vec![1, 2, 3]);
+        assert_eq!(sorted_ids(torrents, SortColumn::Id, true), vec![3, 2, 1]);
+    }
+
+    #[test]
+    fn sorts_names_ignoring_case() {
+        let torrents = vec![torrent(1, "beta"), torrent(2, "Alpha"), torrent(3, "gamma")];
+        assert_eq!(sorted_ids(torrents, SortColumn::Name, false), vec![2, 1, 3]);
+    }
+
+    #[test]
+    fn sorts_done_eta_first_and_unknown_eta_last() {
+        let torrents = vec![
+            TransmissionTorrent { eta: -1, ..torrent(1, "unknown") },
+            TransmissionTorrent { eta: 60, ..torrent(2, "a minute") },
+            TransmissionTorrent { left_until_done: 0, ..torrent(3, "done") },
+            TransmissionTorrent { eta: 5, ..torrent(4, "seconds") },
+        ];
+        assert_eq!(sorted_ids(torrents, SortColumn::Eta, false), vec![3, 4, 2, 1]);
+    }
+
+    #[test]
+    fn sorts_by_numbers() {
+        let torrents = vec![
+            TransmissionTorrent { rate_download: 20, rate_upload: 1, size_when_done: 300, left_until_done: 0, added_date: 2, ..torrent(1, "a") },
+            TransmissionTorrent { rate_download: 10, rate_upload: 3, size_when_done: 100, left_until_done: 50, added_date: 3, ..torrent(2, "b") },
+            TransmissionTorrent { rate_download: 30, rate_upload: 2, size_when_done: 200, left_until_done: 200, added_date: 1, ..torrent(3, "c") },
+        ];
+        assert_eq!(sorted_ids(torrents.clone(), SortColumn::Download, false), vec![2, 1, 3]);
+        assert_eq!(sorted_ids(torrents.clone(), SortColumn::Upload, false), vec![1, 3, 2]);
+        assert_eq!(sorted_ids(torrents.clone(), SortColumn::Size, false), vec![2, 3, 1]);
+        assert_eq!(sorted_ids(torrents.clone(), SortColumn::Downloaded, false), vec![3, 2, 1]);
+        assert_eq!(sorted_ids(torrents.clone(), SortColumn::Done, false), vec![3, 2, 1]);
+        assert_eq!(sorted_ids(torrents, SortColumn::AddedOn, false), vec![3, 1, 2]);
+    }
+
+    #[test]
+    fn breaks_ties_by_id() {
+        let torrents = vec![torrent(3, "same"), torrent(1, "Same"), torrent(2, "same")];
+        assert_eq!(sorted_ids(torrents, SortColumn::Name, false), vec![1, 2, 3]);
+    }
+
+    #[test]
+    fn descending_reverses_the_whole_order_ties_included() {
+        let torrents = vec![
+            TransmissionTorrent { rate_upload: 5, ..torrent(1, "a") },
+            TransmissionTorrent { rate_upload: 9, ..torrent(2, "b") },
+            TransmissionTorrent { rate_upload: 5, ..torrent(3, "c") },
+        ];
+        assert_eq!(sorted_ids(torrents.clone(), SortColumn::Upload, false), vec![1, 3, 2]);
+        assert_eq!(sorted_ids(torrents, SortColumn::Upload, true), vec![2, 3, 1]);
+    }
+}
@@ -6,115 +6,210 @@ use crossterm::event::{Event, KeyCode, KeyEvent, KeyEventKind};
 use ratatui::buffer::Buffer;
 use ratatui::layout::Constraint::{Fill, Length, Min, Percentage};
 use ratatui::layout::{Constraint, Direction, Layout, Margin, Rect, Size};
-use ratatui::prelude::{Line, Stylize};
+use ratatui::prelude::{Line, Modifier, Stylize};
 use ratatui::style::{Color, Style};
 use ratatui::symbols::border;
 use ratatui::text::{Span, Text};
 use ratatui::widgets::{
-    Bar, BarChart, BarGroup, Block, HighlightSpacing, LineGauge, List, ListItem, Padding,
-    Paragraph, Scrollbar, ScrollbarOrientation, ScrollbarState, Widget, Wrap,
+    Block, HighlightSpacing, LineGauge, List, ListItem, Padding, Paragraph, Scrollbar,
+    ScrollbarOrientation, ScrollbarState, Widget,
 };
 use ratatui::{Frame, symbols};
 use std::ops::Add;
 use tui_scrollview::{ScrollView, ScrollViewState};
-use crate::dto::transmission_dto::TransmissionTorrent;
+use std::sync::Arc;
+use ratatui::widgets::Tabs;
+use crate::dto::transmission_dto::{TransmissionTorrent, TransmissionTorrentFiles};
 use crate::service::transmission_service::TransmissionService;
 use crate::util::Util;
+use crate::error::RustorResult;
+use crate::poller::Poller;
+use crate::screen::files_tab::FilesTab;
+use crate::screen::peers_tab::PeersTab;
+use crate::screen::piece_map::PieceMap;
+use crate::screen::trackers_tab::TrackersTab;
+use crate::screen::rate_graph::RateGraph;
+use crate::rate_history::RateHistory;
+use crate::screen::popup_screen::{Notifier, PopupLevel};
+use crate::store::TorrentStore;
+
+// Tabs above the details of the torrent
+#[derive(Default, Clone, Copy, PartialEq)]
+enum InfoTab {
+    #[default]
+    Overview,
+    Files,
+    Peers,
+    Trackers
+}
+
+impl InfoTab {
+
+    const ALL: [InfoTab; 4] = [
+        InfoTab::Overview,
+        InfoTab::Files,
+        InfoTab::Peers,
+        InfoTab::Trackers
+    ];
+
+    fn title(&self) -> &'static str {
+        match self {
+            InfoTab::Overview => "Overview",
+            InfoTab::Files => "Files",
+            InfoTab::Peers => "Peers",
+            InfoTab::Trackers => "Trackers"
+        }
+    }
+
+    fn index(&self) -> usize {
+        Self::ALL.iter().position(|tab| tab == self).unwrap_or(0)
+    }
+
+    fn next(&self) -> Self {
+        Self::ALL[(self.index() + 1) % Self::ALL.len()]
+    }
+
+    fn previous(&self) -> Self {
+        Self::ALL[(self.index() + Self::ALL.len() - 1) % Self::ALL.len()]
+    }
+}
 
 pub struct InfoScreen {
     config_key_bindings: HashMap<ConfigKeyBindingKey, char>,
-    selected_row_torrent: TransmissionTorrent,
+    transmission_service: Arc<TransmissionService>,
+    notifier: Notifier,
+    poller: Poller,
+    torrent_store: TorrentStore,
+    selected_row_torrent: RustorResult<TransmissionTorrent>,
+    tab: InfoTab,
+    files_tab: FilesTab,
+    peers_tab: PeersTab,
+    trackers_tab: TrackersTab,
+    rate_history: RateHistory,
     vertical_scroll_state: ScrollbarState,
     scroll_view_state: ScrollViewState,
     vertical_scroll: usize,
-    text: [String; 3],
 }
 
 impl InfoScreen {
-    pub fn new(config_key_bindings: HashMap<ConfigKeyBindingKey, char>) -> Self {
+    pub fn new(
+        config_key_bindings: HashMap<ConfigKeyBindingKey, char>,
+        transmission_service: Arc<TransmissionService>,
+        notifier: Notifier,
+        poller: Poller,
+        torrent_store: TorrentStore,
+        rate_history: RateHistory
+    ) -> Self {
         Self {
             config_key_bindings,
-            selected_row_torrent: Default::default(),
+            selected_row_torrent: Ok(TransmissionTorrent::default()),
+            tab: InfoTab::default(),
+            files_tab: FilesTab::new(transmission_service.clone(), notifier.clone(), poller.clone()),
+            peers_tab: PeersTab::new(),
+            trackers_tab: TrackersTab::new(transmission_service.clone(), notifier.clone(), poller.clone()),
+            transmission_service,
+            notifier,
+            poller,
+            torrent_store,
+            rate_history,
             vertical_scroll_state: ScrollbarState::default(),
             scroll_view_state: ScrollViewState::default(),
             vertical_scroll: 0,
-            text: [String::new(), String::new(), String::new()],
         }
     }
 
-    fn line_numbers(&self, height: u16) -> impl Widget {
-        use std::fmt::Write;
-        let line_numbers = (1..=height).fold(String::new(), |mut output, n| {
-            let _ = writeln!(output, "{n:>4} ");
-            output
-        });
-        Text::from(line_numbers).dim()
+    // Keep the latest details published by the `Poller` (or the error fetching them)
+    pub fn update(&mut self, selected_row_torrent: RustorResult<TransmissionTorrent>) {
+        self.selected_row_torrent = selected_row_torrent;
     }
 
-    fn bars(&self) -> BarGroup<'static> {
-        let char_data: [(&str, u64, Color); 3] = [
-            ("Red", 2, Color::Red),
-            ("Green", 7, Color::Green),
-            ("Blue", 11, Color::Blue),
-        ];
-        let data = char_data.map(|(label, value, color)| {
-            Bar::default().label(label.into()).value(value).style(color)
-        });
-        BarGroup::default().bars(&data)
+    // Id of the torrent shown, if its details could be fetched (daemon ids start at 1)
+    pub fn torrent_id(&self) -> Option<i64> {
+        self.selected_row_torrent.as_ref().ok().map(|torrent| torrent.id).filter(|id| *id > 0)
     }
 
-    fn vertical_bar_chart(&self) -> impl Widget {
-        let block = Block::bordered().title("Vertical Bar Chart");
-        BarChart::default()
-            .direction(Direction::Vertical)
-            .block(block)
-            .bar_width(5)
-            .bar_gap(1)
-            .data(self.bars())
+    // The outcome is reported by Home once the daemon is done checking
+    fn verify(&mut self) {
+        let Some(torrent_id) = self.torrent_id() else {
+            return;
+        };
+        let result = self.transmission_service.torrent_verify(&[torrent_id]);
+        self.notifier.result(&result, format!("Verifying torrent {}", torrent_id));
+        if result.is_ok() {
+            self.torrent_store.verify(&[torrent_id]);
+        }
+        self.poller.refresh();
     }
 
-    fn text(&self, index: usize) -> impl Widget {
-        let block = Block::bordered().title(format!("Text {index}"));
-        Paragraph::new(self.text[index].clone())
-            .wrap(Wrap { trim: false })
-            .block(block)
+    // Peers come and go between polls, the Peers tab fetches them when opened or asked to
+    fn refresh_peers(&mut self) {
+        let Some(torrent_id) = self.torrent_id() else {
+            return;
+        };
+        match self.transmission_service.torrent_peers(torrent_id) {
+            Ok(peers) => {
+                if let Ok(torrent) = &mut self.selected_row_torrent && torrent.id == torrent_id {
+                    torrent.peers = peers;
+                }
+            }
+            Err(e) => self.notifier.toast(PopupLevel::Error, e.to_string())
+        }
     }
-}
-
-pub struct InfoScreenArgs {
-    selected_row_torrent: TransmissionTorrent
-}
 
-impl InfoScreenArgs {
-    pub fn new(selected_row_torrent: TransmissionTorrent) -> Self {
-        Self { selected_row_torrent }
+    fn switch_tab(&mut self, tab: InfoTab) {
+        self.tab = tab;
+        if tab == InfoTab::Peers {
+            self.refresh_peers();
+        }
     }
-    
-    pub fn get_selected_row_torrent(&self) -> TransmissionTorrent {
-        self.selected_row_torrent.clone()
+
+    fn tabs(&self) -> Tabs<'static> {
+        Tabs::new(InfoTab::ALL.iter().map(|tab| tab.title()))
+            .select(self.tab.index())
+            .highlight_style(Style::default().add_modifier(Modifier::REVERSED))
     }
 }
 
-impl RenderableArgs for InfoScreenArgs {}
-
-impl Renderable<InfoScreenArgs> for InfoScreen {
-    fn render(&mut self, frame: &mut Frame, args: InfoScreenArgs) {
-        self.selected_row_torrent = args.get_selected_row_torrent();
-        let torrent = self.selected_row_torrent.clone();
+impl Renderable<EmptyRenderableArgs> for InfoScreen {
+    fn render(&mut self, frame: &mut Frame, _args: EmptyRenderableArgs) {
+        let torrent = match self.selected_row_torrent.clone() {
+            Ok(torrent) => torrent,
+            Err(e) => {
+                let block = Block::bordered().padding(Padding::uniform(1));
+                frame.render_widget(Paragraph::new(e.to_string().red()).centered().block(block), frame.area());
+                return;
+            }
+        };
 
-        let scroll_view_height = 30;
-        let buf = frame.buffer_mut();
+        let [tabs_area, content_area] = Layout::vertical([Length(1), Fill(1)]).areas(frame.area());
+        frame.render_widget(self.tabs(), tabs_area);
+        match self.tab {
+            InfoTab::Files => {
+                self.files_tab.render(frame, content_area, torrent.id, &TransmissionTorrentFiles::from(&torrent));
+                return;
+            }
+            InfoTab::Peers => {
+                self.peers_tab.render(frame, content_area, &torrent.peers);
+                return;
+            }
+            InfoTab::Trackers => {
+                self.trackers_tab.render(frame, content_area, &torrent);
+                return;
+            }
+            InfoTab::Overview => {}
+        }
 
-        let width = if buf.area.height < scroll_view_height {
-            buf.area.width - 1
+        let scroll_view_height = 45;
+        let width = if content_area.height < scroll_view_height {
+            content_area.width.saturating_sub(1)
         } else {
-            buf.area.width
+            content_area.width
         };
         let mut scroll_view = ScrollView::new(Size::new(width, scroll_view_height));
         let scroll_view_buf = scroll_view.buf_mut();
 
-        let [gauge_area, info_area, peers_area, bottom_area] =
-            Layout::vertical([Length(5), Min(1), Min(1), Length(1)])
+        let [gauge_area, info_area, peers_area, rates_area, pieces_area, bottom_area] =
+            Layout::vertical([Length(5), Min(1), Min(1), Length(6), Length(7), Length(1)])
                 .spacing(1)
                 .vertical_margin(1)
                 .horizontal_margin(1)
@@ -129,11 +224,18 @@ impl Renderable<InfoScreenArgs> for InfoScreen {
             ))
             .padding(Padding::uniform(1));
         let gauge_style = Style::new().bg(Color::DarkGray).fg(Color::Gray).bold();
+        // how far the data has been checked while verifying
+        let (ratio, label) = if torrent.is_checking() {
+            (torrent.recheck_progress, format!("Checking {:.0} %", 100f64 * torrent.recheck_progress))
+        } else {
+            (torrent.calc_ratio(), torrent.percentage_done())
+        };
         LineGauge::default()
             .block(block)
             .filled_style(gauge_style)
             .line_set(symbols::line::NORMAL)
-            .ratio(torrent.calc_ratio())
+            .ratio(ratio.clamp(0f64, 1f64))
+            .label(label)
             .render(gauge_area, scroll_view_buf);
 
         // info
@@ -187,9 +289,31 @@ impl Renderable<InfoScreenArgs> for InfoScreen {
         .highlight_spacing(HighlightSpacing::Always)
         .render(peers_area, scroll_view_buf);
 
+        // rates
+        block = Block::bordered()
+            .title(" Transfer rates ")
+            .padding(Padding::horizontal(1));
+        let rate_graph_area = block.inner(rates_area);
+        block.render(rates_area, scroll_view_buf);
+        RateGraph::new(self.rate_history.torrent(torrent.id)).render(rate_graph_area, scroll_view_buf);
+
+        // pieces
+        let piece_map = PieceMap::new(&torrent);
+        block = Block::bordered()
+            .title(piece_map.title())
+            .title_bottom(PieceMap::legend().centered())
+            .padding(Padding::horizontal(1));
+        let piece_map_area = block.inner(pieces_area);
+        block.render(pieces_area, scroll_view_buf);
+        piece_map.render(piece_map_area, scroll_view_buf);
+
         // bottom
         let mut key_bindings_block = KeyBindingsBlock::new(self.config_key_bindings.clone());
         let key_bindings = vec![
+            KeyBindingsBlock::kb_tab(),
+            KeyBindingsBlock::kb_verify(),
+            key_bindings_block.cnf_kb_limits(),
+            key_bindings_block.cnf_kb_move(),
             key_bindings_block.cnf_kb_home(),
             key_bindings_block.cnf_kb_add(),
             key_bindings_block.cnf_kb_search(),
@@ -201,7 +325,7 @@ impl Renderable<InfoScreenArgs> for InfoScreen {
             .centered()
             .render(bottom_area, scroll_view_buf);
 
-        frame.render_stateful_widget(scroll_view, frame.area(), &mut self.scroll_view_state);
+        frame.render_stateful_widget(scroll_view, content_area, &mut self.scroll_view_state);
     }
 }
 
@@ -209,6 +333,37 @@ impl KeyEventHandler for InfoScreen {
     fn handle_key_event(&mut self, key_event: KeyEvent, event: Event) -> bool {
         if key_event.kind == KeyEventKind::Press {
             match key_event.code {
+                // typing an announce url
+                _ if self.tab == InfoTab::Trackers && self.trackers_tab.is_editing() => {
+                    self.trackers_tab.handle_key_event(key_event, event)
+                }
+                // typing a new name, show it right away instead of after the next poll
+                _ if self.tab == InfoTab::Files && self.files_tab.is_editing() => {
+                    self.files_tab.handle_key_event(key_event, event);
+                    if let Some((path, name)) = self.files_tab.take_renamed() && let Ok(torrent) = &mut self.selected_row_torrent {
+                        torrent.rename_path(&path, &name);
+                    }
+                    true
+                }
+                KeyCode::Tab => {
+                    self.switch_tab(self.tab.next());
+                    true
+                }
+                KeyCode::BackTab => {
+                    self.switch_tab(self.tab.previous());
+                    true
+                }
+                KeyCode::Char('v') => {
+                    self.verify();
+                    true
+                }
+                _ if self.tab == InfoTab::Files => self.files_tab.handle_key_event(key_event, event),
+                KeyCode::Char('r') if self.tab == InfoTab::Peers => {
+                    self.refresh_peers();
+                    true
+                }
+                _ if self.tab == InfoTab::Peers => self.peers_tab.handle_key_event(key_event, event),
+                _ if self.tab == InfoTab::Trackers => self.trackers_tab.handle_key_event(key_event, event),
                 KeyCode::Char('j') | KeyCode::Down => {
                     self.scroll_view_state.scroll_down();
                     true
@@ -94,6 +94,10 @@ impl KeyBindingsBlock {
         KeyBindingItem::new_ctrl_and_char("Open", *self.config_key_bindings.get(&ConfigKeyBindingKey::KbOpen).unwrap())
     }
 
+    pub fn cnf_kb_profiles(&mut self) -> KeyBindingItem {
+        KeyBindingItem::new_ctrl_and_char("Profiles", *self.config_key_bindings.get(&ConfigKeyBindingKey::KbProfiles).unwrap())
+    }
+
     pub fn cnf_kb_quit(&mut self) -> KeyBindingItem {
         KeyBindingItem::new_ctrl_and_char("Quit", *self.config_key_bindings.get(&ConfigKeyBindingKey::KbQuit).unwrap())
     }
@@ -106,7 +110,147 @@ impl KeyBindingsBlock {
         KeyBindingItem::new_ctrl_and_char("Search", *self.config_key_bindings.get(&ConfigKeyBindingKey::KbSearch).unwrap())
     }
 
+    pub fn cnf_kb_limits(&mut self) -> KeyBindingItem {
+        KeyBindingItem::new_ctrl_and_char("Limits", *self.config_key_bindings.get(&ConfigKeyBindingKey::KbLimits).unwrap())
+    }
+
+    pub fn cnf_kb_move(&mut self) -> KeyBindingItem {
+        KeyBindingItem::new_ctrl_and_char("Move", *self.config_key_bindings.get(&ConfigKeyBindingKey::KbMove).unwrap())
+    }
+
+    pub fn cnf_kb_settings(&mut self) -> KeyBindingItem {
+        KeyBindingItem::new_ctrl_and_char("Settings", *self.config_key_bindings.get(&ConfigKeyBindingKey::KbSettings).unwrap())
+    }
+
+    pub fn cnf_kb_groups(&mut self) -> KeyBindingItem {
+        KeyBindingItem::new_ctrl_and_char("Groups", *self.config_key_bindings.get(&ConfigKeyBindingKey::KbGroups).unwrap())
+    }
+
+    pub fn cnf_kb_stats(&mut self) -> KeyBindingItem {
+        KeyBindingItem::new_ctrl_and_char("Stats", *self.config_key_bindings.get(&ConfigKeyBindingKey::KbStats).unwrap())
+    }
+
     pub fn kb_cancel() -> KeyBindingItem {
         KeyBindingItem::new_key_code("Cancel", KeyCode::Esc)
     }
+
+    pub fn kb_option() -> KeyBindingItem {
+        KeyBindingItem::new_key_code("Option", KeyCode::Tab)
+    }
+
+    pub fn kb_connect() -> KeyBindingItem {
+        KeyBindingItem::new_key_code("Connect", KeyCode::Enter)
+    }
+
+    pub fn kb_refresh() -> KeyBindingItem {
+        KeyBindingItem::new_key_code("Refresh", KeyCode::Char('r'))
+    }
+
+    pub fn kb_mark() -> KeyBindingItem {
+        KeyBindingItem::new_key_code("Mark", KeyCode::Char(' '))
+    }
+
+    pub fn kb_filter() -> KeyBindingItem {
+        KeyBindingItem::new_key_code("Filter", KeyCode::Char('/'))
+    }
+
+    pub fn kb_status() -> KeyBindingItem {
+        KeyBindingItem::new_key_code("Status", KeyCode::Tab)
+    }
+
+    pub fn kb_sort() -> KeyBindingItem {
+        KeyBindingItem::new_key_code("Sort", KeyCode::Char('o'))
+    }
+
+    pub fn kb_group() -> KeyBindingItem {
+        KeyBindingItem::new_key_code("Group", KeyCode::Char('g'))
+    }
+
+    pub fn kb_faster() -> KeyBindingItem {
+        KeyBindingItem::new_key_code("Faster", KeyCode::Char('+'))
+    }
+
+    pub fn kb_slower() -> KeyBindingItem {
+        KeyBindingItem::new_key_code("Slower", KeyCode::Char('-'))
+    }
+
+    pub fn kb_tab() -> KeyBindingItem {
+        KeyBindingItem::new_key_code("Tab", KeyCode::Tab)
+    }
+
+    pub fn kb_expand() -> KeyBindingItem {
+        KeyBindingItem::new_key_code("Expand", KeyCode::Enter)
+    }
+
+    pub fn kb_wanted() -> KeyBindingItem {
+        KeyBindingItem::new_key_code("Wanted", KeyCode::Char(' '))
+    }
+
+    pub fn kb_priority_high() -> KeyBindingItem {
+        KeyBindingItem::new_key_code("High", KeyCode::Char('h'))
+    }
+
+    pub fn kb_priority_normal() -> KeyBindingItem {
+        KeyBindingItem::new_key_code("Normal", KeyCode::Char('n'))
+    }
+
+    pub fn kb_priority_low() -> KeyBindingItem {
+        KeyBindingItem::new_key_code("Low", KeyCode::Char('l'))
+    }
+
+    pub fn kb_edit() -> KeyBindingItem {
+        KeyBindingItem::new_key_code("Edit", KeyCode::Enter)
+    }
+
+    pub fn kb_apply() -> KeyBindingItem {
+        KeyBindingItem::new_key_code("Apply", KeyCode::Char('a'))
+    }
+
+    pub fn kb_confirm() -> KeyBindingItem {
+        KeyBindingItem::new_key_code("Confirm", KeyCode::Enter)
+    }
+
+    pub fn kb_add_tracker() -> KeyBindingItem {
+        KeyBindingItem::new_key_code("Add", KeyCode::Char('a'))
+    }
+
+    pub fn kb_edit_tracker() -> KeyBindingItem {
+        KeyBindingItem::new_key_code("Replace", KeyCode::Char('e'))
+    }
+
+    pub fn kb_remove_tracker() -> KeyBindingItem {
+        KeyBindingItem::new_key_code("Remove", KeyCode::Char('d'))
+    }
+
+    pub fn kb_verify() -> KeyBindingItem {
+        KeyBindingItem::new_key_code("Verify", KeyCode::Char('v'))
+    }
+
+    pub fn kb_rename() -> KeyBindingItem {
+        KeyBindingItem::new_key_code("Rename", KeyCode::Char('r'))
+    }
+
+    pub fn kb_complete() -> KeyBindingItem {
+        KeyBindingItem::new_key_code("Complete", KeyCode::Tab)
+    }
+
+    pub fn kb_move_mode() -> KeyBindingItem {
+        KeyBindingItem::new_key_code("Mode", KeyCode::BackTab)
+    }
+
+    pub fn kb_recent() -> KeyBindingItem {
+        KeyBindingItem::new_key_code("Recent", KeyCode::Up)
+    }
+
+    pub fn kb_new_group() -> KeyBindingItem {
+        KeyBindingItem::new_key_code("New", KeyCode::Char('n'))
+    }
+
+    pub fn kb_assign() -> KeyBindingItem {
+        KeyBindingItem::new_key_code("Assign", KeyCode::Char('a'))
+    }
+
+    pub fn kb_unassign() -> KeyBindingItem {
+        KeyBindingItem::new_key_code("Unassign", KeyCode::Char('u'))
+    }
 }
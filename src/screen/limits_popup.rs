@@ -0,0 +1,167 @@
+use std::sync::Arc;
+use crossterm::event::{Event, KeyEvent};
+use ratatui::Frame;
+use ratatui::layout::{Constraint, Flex, Layout};
+use ratatui::prelude::{Line, Stylize};
+use ratatui::symbols::border;
+use ratatui::widgets::{Block, Clear, Padding};
+use serde_json::Value;
+use crate::app::{EmptyRenderableArgs, KeyEventHandler, Renderable};
+use crate::dto::transmission_dto::{PRIORITY_HIGH, PRIORITY_LOW, PRIORITY_NORMAL};
+use crate::poller::Poller;
+use crate::screen::form::{field, FieldKind, Form, FormAction, Section, COUNT};
+use crate::screen::key_bindings_block::KeyBindingsBlock;
+use crate::screen::popup_screen::{Notifier, PopupLevel};
+use crate::service::transmission_service::TransmissionService;
+
+// `seedRatioMode` / `seedIdleMode`
+const SEED_MODES: FieldKind = FieldKind::Enum(&[(0, "global"), (1, "custom"), (2, "unlimited")]);
+
+// See `torrent-set` in https://github.com/transmission/transmission/blob/main/docs/rpc-spec.md
+const SECTIONS: &[Section] = &[
+    ("Speed", &[
+        field("downloadLimited", "Limit download", FieldKind::Bool),
+        field("downloadLimit", "Download limit (kB/s)", COUNT),
+        field("uploadLimited", "Limit upload", FieldKind::Bool),
+        field("uploadLimit", "Upload limit (kB/s)", COUNT),
+        field("honorsSessionLimits", "Honor session limits", FieldKind::Bool),
+        field("bandwidthPriority", "Bandwidth priority", FieldKind::Enum(&[
+            (PRIORITY_LOW, "low"), (PRIORITY_NORMAL, "normal"), (PRIORITY_HIGH, "high")
+        ])),
+    ]),
+    ("Seeding", &[
+        field("seedRatioMode", "Stop at ratio", SEED_MODES),
+        field("seedRatioLimit", "Ratio", FieldKind::Float),
+        field("seedIdleMode", "Stop when idle", SEED_MODES),
+        field("seedIdleLimit", "Idle limit (minutes)", COUNT),
+    ]),
+    ("Peers", &[
+        field("peer-limit", "Peer limit", FieldKind::Int { min: 1, max: 65535 }),
+    ]),
+];
+
+// rows of the form, the borders and the padding
+const HEIGHT: u16 = 22;
+
+/*
+    Popup drawn over Home or Info to edit the speed, seeding and peer limits of some torrents.
+    It shows the values of the first torrent, but only the fields actually edited are sent with `torrent-set`,
+    so applying to a selection leaves whatever else differs between its torrents alone.
+*/
+pub struct LimitsPopup {
+    transmission_service: Arc<TransmissionService>,
+    notifier: Notifier,
+    poller: Poller,
+    form: Form,
+    // the torrents being edited, none while the popup is closed
+    tor_ids: Vec<i64>,
+    // of the first torrent
+    name: String
+}
+
+impl LimitsPopup {
+
+    pub fn new(transmission_service: Arc<TransmissionService>, notifier: Notifier, poller: Poller) -> Self {
+        Self {
+            transmission_service,
+            form: Form::new(SECTIONS, notifier.clone()),
+            notifier,
+            poller,
+            tor_ids: vec![],
+            name: String::new()
+        }
+    }
+
+    pub fn is_open(&self) -> bool {
+        !self.tor_ids.is_empty()
+    }
+
+    /*
+        Fetch the limits of the given torrents, the popup stays closed when that fails.
+        The form starts from the values of the first one, whatever order the daemon answers in.
+    */
+    pub fn open(&mut self, tor_ids: Vec<i64>) {
+        let Some(first_id) = tor_ids.first().copied() else {
+            self.notifier.toast(PopupLevel::Warning, "No torrent selected");
+            return;
+        };
+        let mut fields = self.form.keys();
+        fields.extend(["id", "name"]);
+        let torrents = match self.transmission_service.torrent_get_values(&tor_ids, &fields) {
+            Ok(torrents) => torrents,
+            Err(e) => {
+                self.notifier.modal(PopupLevel::Error, e.to_string());
+                return;
+            }
+        };
+        match torrents.into_iter().find(|values| values.get("id").and_then(Value::as_i64) == Some(first_id)) {
+            Some(values) => {
+                self.name = values.get("name").and_then(Value::as_str).unwrap_or_default().to_string();
+                self.form.reset(values);
+                self.tor_ids = tor_ids;
+            }
+            None => self.notifier.toast(PopupLevel::Warning, "The selected torrent is gone")
+        }
+    }
+
+    fn close(&mut self) {
+        self.tor_ids.clear();
+    }
+
+    fn apply(&mut self) {
+        let result = self.transmission_service.torrent_set_values(&self.tor_ids, self.form.edits());
+        self.notifier.result(&result, format!("Saved {} limits of {} torrents", self.form.edit_count(), self.tor_ids.len()));
+        if result.is_ok() {
+            self.close();
+            self.poller.refresh();
+        } else {
+            self.form.browse();
+        }
+    }
+}
+
+impl Renderable<EmptyRenderableArgs> for LimitsPopup {
+    fn render(&mut self, frame: &mut Frame, _args: EmptyRenderableArgs) {
+        let [area] = Layout::horizontal([Constraint::Percentage(60)])
+            .flex(Flex::Center)
+            .areas(frame.area());
+        let [area] = Layout::vertical([Constraint::Length(HEIGHT)])
+            .flex(Flex::Center)
+            .areas(area);
+
+        let mut key_bindings = self.form.key_bindings();
+        if !self.form.is_previewing() {
+            key_bindings.push(KeyBindingsBlock::kb_cancel());
+        }
+        let mut title = match self.tor_ids.len() {
+            1 => format!(" Limits of {} ", self.name),
+            count => format!(" Limits of {} torrents (showing {}) ", count, self.name)
+        };
+        if self.form.edit_count() > 0 {
+            title = format!("{}({} changed) ", title, self.form.edit_count());
+        }
+        let block = Block::bordered()
+            .title(Line::from(title.bold()).centered())
+            .title_bottom(KeyBindingsBlock::key_bindings_as_line(&key_bindings).centered())
+            .padding(Padding::uniform(1))
+            .border_set(border::THICK);
+        frame.render_widget(Clear, area);
+        frame.render_widget(&block, area);
+        self.form.render(frame, block.inner(area));
+    }
+}
+
+impl KeyEventHandler for LimitsPopup {
+    /*
+       Returns false once the popup is closed
+    */
+    fn handle_key_event(&mut self, key_event: KeyEvent, event: Event) -> bool {
+        match self.form.handle_key_event(key_event, event) {
+            FormAction::Apply => self.apply(),
+            FormAction::Reload => self.open(self.tor_ids.clone()),
+            FormAction::Leave => self.close(),
+            FormAction::None => {}
+        }
+        self.is_open()
+    }
+}
@@ -1,10 +1,23 @@
 pub mod add_screen;
+pub mod files_tab;
+pub mod form;
+pub mod groups_screen;
 pub mod help_screen;
 pub mod home_screen;
 pub mod info_screen;
+pub mod limits_popup;
+pub mod move_popup;
+pub mod peers_tab;
+pub mod piece_map;
+pub mod popup_screen;
+pub mod profiles_screen;
+pub mod rate_graph;
 pub mod reann_screen;
 pub mod rm_screen;
 pub mod search_screen;
 pub mod search_res_screen;
 pub mod search_info_screen;
+pub mod settings_screen;
+pub mod stats_screen;
+pub mod trackers_tab;
 mod key_bindings_block;
\ No newline at end of file
@@ -0,0 +1,269 @@
+use std::collections::BTreeSet;
+use std::fs;
+use std::sync::Arc;
+use crossterm::event::{Event, KeyCode, KeyEvent, KeyEventKind};
+use ratatui::Frame;
+use ratatui::layout::{Constraint, Flex, Layout};
+use ratatui::prelude::{Line, Stylize};
+use ratatui::symbols::border;
+use ratatui::widgets::{Block, Clear, Padding, Paragraph};
+use tui_input::Input;
+use tui_input::backend::crossterm::EventHandler;
+use crate::app::{EmptyRenderableArgs, KeyEventHandler, Renderable};
+use crate::dto::transmission_dto::TransmissionTorrent;
+use crate::poller::Poller;
+use crate::screen::key_bindings_block::KeyBindingsBlock;
+use crate::screen::popup_screen::{Notifier, PopupLevel};
+use crate::service::transmission_service::TransmissionService;
+use crate::store::TorrentStore;
+
+const MAX_RECENT_DIRS: usize = 10;
+// completions listed below the input
+const MAX_MATCHES: usize = 5;
+const HEIGHT: u16 = 14;
+
+/*
+    Popup drawn over Home or Info to change where the data of some torrents lives with `torrent-set-location`,
+    either moving the data or only pointing the torrents at data which is already there.
+    Tab completes the directories of the known torrents and the recent destinations, which are remembered across runs
+    by the app, and when the daemon runs on this host the directories of the filesystem as well.
+*/
+pub struct MovePopup {
+    transmission_service: Arc<TransmissionService>,
+    notifier: Notifier,
+    poller: Poller,
+    torrent_store: TorrentStore,
+    // the torrents being moved, none while the popup is closed
+    tor_ids: Vec<i64>,
+    input: Input,
+    // false: only look for the data in the new location
+    move_data: bool,
+    // latest first
+    recent_dirs: Vec<String>,
+    // recent destination shown in the input, if any
+    recent_index: Option<usize>,
+    // left by the last completion when it was ambiguous
+    matches: Vec<String>
+}
+
+impl MovePopup {
+
+    pub fn new(
+        transmission_service: Arc<TransmissionService>,
+        notifier: Notifier,
+        poller: Poller,
+        torrent_store: TorrentStore,
+        recent_dirs: Vec<String>
+    ) -> Self {
+        Self {
+            transmission_service,
+            notifier,
+            poller,
+            torrent_store,
+            tor_ids: vec![],
+            input: Input::default(),
+            move_data: true,
+            recent_dirs,
+            recent_index: None,
+            matches: vec![]
+        }
+    }
+
+    pub fn is_open(&self) -> bool {
+        !self.tor_ids.is_empty()
+    }
+
+    pub fn recent_dirs(&self) -> &[String] {
+        &self.recent_dirs
+    }
+
+    // Start from the location of the first torrent
+    pub fn open(&mut self, tor_ids: Vec<i64>) {
+        let tor_ids: Vec<i64> = tor_ids.into_iter().filter(|id| self.torrent_store.get(*id).is_some()).collect();
+        let Some(torrent) = tor_ids.first().and_then(|id| self.torrent_store.get(*id)) else {
+            self.notifier.toast(PopupLevel::Warning, "No torrent selected");
+            return;
+        };
+        self.input = Input::new(torrent.download_dir);
+        self.move_data = true;
+        self.recent_index = None;
+        self.matches.clear();
+        self.tor_ids = tor_ids;
+    }
+
+    fn close(&mut self) {
+        self.tor_ids.clear();
+    }
+
+    fn torrents(&self) -> Vec<TransmissionTorrent> {
+        self.tor_ids.iter().filter_map(|id| self.torrent_store.get(*id)).collect()
+    }
+
+    /*
+        Known directories plus, for a daemon on this host, the subdirectories of the typed one, all ending with a slash.
+        The filesystem of a remote daemon is not ours to list.
+    */
+    fn candidates(&self, prefix: &str) -> BTreeSet<String> {
+        let known = self.torrent_store.torrents().into_iter().map(|torrent| torrent.download_dir).chain(self.recent_dirs.iter().cloned());
+        let mut candidates: BTreeSet<String> = known.map(|dir| format!("{}/", dir.trim_end_matches('/'))).collect();
+        if self.transmission_service.is_local() && let Some((parent, name)) = prefix.rsplit_once('/') {
+            let dir = if parent.is_empty() { "/" } else { parent };
+            let entries = fs::read_dir(dir).into_iter().flatten().flatten();
+            candidates.extend(entries
+                .filter(|entry| entry.file_type().is_ok_and(|file_type| file_type.is_dir()))
+                .filter_map(|entry| entry.file_name().into_string().ok())
+                // hidden directories only when asked for
+                .filter(|entry_name| name.starts_with('.') || !entry_name.starts_with('.'))
+                .map(|entry_name| format!("{}/{}/", parent, entry_name)));
+        }
+        candidates.into_iter().filter(|candidate| candidate.starts_with(prefix) && candidate != prefix).collect()
+    }
+
+    // Complete as far as all candidates agree, the ambiguous rest is listed below the input
+    fn complete(&mut self) {
+        let prefix = self.input.value().to_string();
+        let candidates: Vec<String> = self.candidates(&prefix).into_iter().collect();
+        let common = candidates.iter().skip(1).fold(candidates.first().cloned().unwrap_or(prefix.clone()), |common, candidate| {
+            common.chars().zip(candidate.chars()).take_while(|(a, b)| a == b).map(|(a, _)| a).collect()
+        });
+        if candidates.is_empty() {
+            self.notifier.toast(PopupLevel::Warning, format!("No directory starting with {}", prefix));
+        }
+        self.input = Input::new(common);
+        self.matches = if candidates.len() > 1 { candidates } else { vec![] };
+    }
+
+    // Up goes to older destinations, down to newer ones
+    fn browse_recent(&mut self, older: bool) {
+        let index = match (self.recent_index, older) {
+            (None, true) => Some(0),
+            (Some(i), true) => Some((i + 1).min(self.recent_dirs.len().saturating_sub(1))),
+            (Some(i), false) => i.checked_sub(1),
+            (None, false) => None
+        };
+        if let Some(dir) = index.and_then(|i| self.recent_dirs.get(i)) {
+            self.input = Input::new(dir.clone());
+        }
+        self.recent_index = index.filter(|i| *i < self.recent_dirs.len());
+        self.matches.clear();
+    }
+
+    fn remember(&mut self, location: &str) {
+        self.recent_dirs.retain(|dir| dir != location);
+        self.recent_dirs.insert(0, location.to_string());
+        self.recent_dirs.truncate(MAX_RECENT_DIRS);
+    }
+
+    fn apply(&mut self) {
+        let location = self.input.value().trim();
+        let location = if location.len() > 1 { location.trim_end_matches('/') } else { location }.to_string();
+        if location.is_empty() {
+            self.notifier.toast(PopupLevel::Error, "Expected a directory");
+            return;
+        }
+        let result = self.transmission_service.torrent_set_location(&self.tor_ids, &location, self.move_data);
+        let done = if self.move_data { "Moving" } else { "Relocated" };
+        match self.torrents().as_slice() {
+            [torrent] => self.notifier.result(&result, format!("{} {} to {}", done, torrent.name, location)),
+            torrents => self.notifier.result(&result, format!("{} {} torrents to {}", done, torrents.len(), location))
+        }
+        if result.is_ok() {
+            self.torrent_store.relocate(&self.tor_ids, &location, self.move_data);
+            self.remember(&location);
+            self.close();
+            self.poller.refresh();
+        }
+    }
+
+    fn option_line(&self, label: &'static str, is_move_option: bool) -> Line<'static> {
+        let mark = if self.move_data == is_move_option { "(x) " } else { "( ) " };
+        let line = Line::from(vec![mark.into(), label.into()]);
+        if self.move_data == is_move_option { line.bold() } else { line }
+    }
+}
+
+impl Renderable<EmptyRenderableArgs> for MovePopup {
+    fn render(&mut self, frame: &mut Frame, _args: EmptyRenderableArgs) {
+        let [area] = Layout::horizontal([Constraint::Percentage(60)])
+            .flex(Flex::Center)
+            .areas(frame.area());
+        let [area] = Layout::vertical([Constraint::Length(HEIGHT)])
+            .flex(Flex::Center)
+            .areas(area);
+
+        let torrents = self.torrents();
+        let title = match torrents.as_slice() {
+            [torrent] => format!(" Move {} ", torrent.name),
+            torrents => format!(" Move {} torrents ", torrents.len())
+        };
+        let key_bindings = vec![
+            KeyBindingsBlock::kb_complete(),
+            KeyBindingsBlock::kb_move_mode(),
+            KeyBindingsBlock::kb_recent(),
+            KeyBindingsBlock::kb_confirm(),
+            KeyBindingsBlock::kb_cancel()
+        ];
+        let block = Block::bordered()
+            .title(Line::from(title.bold()).centered())
+            .title_bottom(KeyBindingsBlock::key_bindings_as_line(&key_bindings).centered())
+            .padding(Padding::uniform(1))
+            .border_set(border::THICK);
+        let inner_area = block.inner(area);
+        frame.render_widget(Clear, area);
+        frame.render_widget(block, area);
+
+        let dirs: BTreeSet<&str> = torrents.iter().map(|torrent| torrent.download_dir.as_str()).collect();
+        let from = match dirs.iter().collect::<Vec<_>>().as_slice() {
+            [dir] => dir.to_string(),
+            _ => format!("{} directories", dirs.len())
+        };
+        let mut matches = self.matches.iter().take(MAX_MATCHES).cloned().collect::<Vec<_>>().join("  ");
+        if self.matches.len() > MAX_MATCHES {
+            matches = format!("{}  (+{} more)", matches, self.matches.len() - MAX_MATCHES);
+        }
+        let [from_area, input_area, matches_area, options_area] = Layout::vertical([
+            Constraint::Length(2),
+            Constraint::Length(1),
+            Constraint::Length(2),
+            Constraint::Fill(1)
+        ]).areas(inner_area);
+        frame.render_widget(Line::from(vec!["From: ".bold(), from.into()]), from_area);
+        frame.render_widget(Line::from(matches.dim()), matches_area);
+        frame.render_widget(Paragraph::new(vec![
+            self.option_line("Move the data there", true),
+            self.option_line("Only look for the data there", false),
+        ]), options_area);
+
+        let label = "To: ";
+        let width = input_area.width.saturating_sub(label.len() as u16 + 1);
+        let scroll = self.input.visual_scroll(width as usize);
+        let value: String = self.input.value().chars().skip(scroll).collect();
+        let x = self.input.visual_cursor().max(scroll) - scroll;
+        frame.set_cursor_position((input_area.x + label.len() as u16 + x as u16, input_area.y));
+        frame.render_widget(Line::from(vec![label.bold(), value.into()]), input_area);
+    }
+}
+
+impl KeyEventHandler for MovePopup {
+    /*
+       Returns false once the popup is closed
+    */
+    fn handle_key_event(&mut self, key_event: KeyEvent, event: Event) -> bool {
+        if key_event.kind == KeyEventKind::Press {
+            match key_event.code {
+                KeyCode::Tab => self.complete(),
+                KeyCode::BackTab => self.move_data = !self.move_data,
+                KeyCode::Up => self.browse_recent(true),
+                KeyCode::Down => self.browse_recent(false),
+                KeyCode::Enter => self.apply(),
+                KeyCode::Esc => self.close(),
+                // let input handle it
+                _ => {
+                    self.input.handle_event(&event);
+                    self.matches.clear();
+                }
+            }
+        }
+        self.is_open()
+    }
+}
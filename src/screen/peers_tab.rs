@@ -0,0 +1,282 @@
+use std::cmp::Ordering;
+use crossterm::event::{Event, KeyCode, KeyEvent, KeyEventKind};
+use ratatui::Frame;
+use ratatui::layout::{Constraint, Layout, Rect};
+use ratatui::prelude::{Color, Line, Modifier, Style, Stylize};
+use ratatui::widgets::{Block, Cell, Padding, Paragraph, Row, Table, TableState, Wrap};
+use crate::app::KeyEventHandler;
+use crate::dto::transmission_dto::TransmissionTorrentPeer;
+use crate::screen::key_bindings_block::KeyBindingsBlock;
+use crate::util::Util;
+
+const COLUMNS: [&str; 9] = ["Address", "Client", "Progress", "Download", "Upload", "Flags", "Enc", "In", "uTP"];
+// Width (in cells) of the progress bar, without the percentage
+const PROGRESS_BAR_WIDTH: usize = 10;
+
+/*
+    Peers tab of the Info screen, one row per connected peer sorted by the focused column.
+    The peers come with the details of the focused torrent refreshed by the `Poller`, the Info screen also fetches them
+    with `torrent_peers` when the tab opens or on refresh.
+*/
+pub struct PeersTab {
+    table_state: TableState,
+    // index in `COLUMNS`
+    sort_column: usize,
+    sort_descending: bool,
+    // the selection follows the peer when rows move around
+    selected_address: Option<String>,
+    peers: Vec<TransmissionTorrentPeer>
+}
+
+impl PeersTab {
+
+    pub fn new() -> Self {
+        Self {
+            table_state: TableState::default().with_selected(0).with_selected_column(0),
+            sort_column: 0,
+            sort_descending: false,
+            selected_address: None,
+            peers: vec![]
+        }
+    }
+
+    pub fn render(&mut self, frame: &mut Frame, area: Rect, peers: &[TransmissionTorrentPeer]) {
+        self.peers = peers.to_vec();
+        self.sort_peers();
+        let selected = self.selected_address
+            .as_ref()
+            .and_then(|address| self.peers.iter().position(|peer| &peer.address == address))
+            .unwrap_or(self.active_row().min(self.peers.len().saturating_sub(1)));
+        self.select(selected);
+
+        let key_bindings = vec![
+            KeyBindingsBlock::kb_sort(),
+            KeyBindingsBlock::kb_refresh(),
+            KeyBindingsBlock::kb_cancel()
+        ];
+        let block = Block::bordered()
+            .title(Line::from(format!(" {} peers ", self.peers.len()).bold()).centered())
+            .title_bottom(KeyBindingsBlock::key_bindings_as_line(&key_bindings).centered())
+            .padding(Padding::horizontal(1));
+        let [table_area, flags_area] = Layout::vertical([Constraint::Fill(1), Constraint::Length(2)]).areas(block.inner(area));
+        frame.render_widget(block, area);
+        frame.render_stateful_widget(self.table(), table_area, &mut self.table_state);
+
+        // meaning of the flags of the selected peer
+        let flags = self.peers
+            .get(self.active_row())
+            .map(|peer| peer.flag_meanings().join(", "))
+            .unwrap_or_default();
+        frame.render_widget(Paragraph::new(format!("Flags: {flags}").dim()).wrap(Wrap { trim: true }), flags_area);
+    }
+
+    fn active_row(&self) -> usize {
+        self.table_state.selected().unwrap_or(0)
+    }
+
+    fn select(&mut self, i: usize) {
+        self.table_state.select(Some(i));
+        self.selected_address = self.peers.get(i).map(|peer| peer.address.clone());
+    }
+
+    fn next_row(&mut self) {
+        self.select((self.active_row() + 1).min(self.peers.len().saturating_sub(1)));
+    }
+
+    fn previous_row(&mut self) {
+        self.select(self.active_row().saturating_sub(1));
+    }
+
+    fn active_column(&self) -> usize {
+        self.table_state.selected_column().unwrap_or(0)
+    }
+
+    fn next_column(&mut self) {
+        self.table_state.select_column(Some((self.active_column() + 1).min(COLUMNS.len() - 1)));
+    }
+
+    fn previous_column(&mut self) {
+        self.table_state.select_column(Some(self.active_column().saturating_sub(1)));
+    }
+
+    // Sort by the focused column, ascending first and then toggling the direction
+    fn sort_by_active_column(&mut self) {
+        let column = self.active_column();
+        self.sort_descending = column == self.sort_column && !self.sort_descending;
+        self.sort_column = column;
+    }
+
+    fn sort_peers(&mut self) {
+        let column = self.sort_column;
+        self.peers.sort_by(|a, b| {
+            let ordering = match column {
+                1 => a.client_name.to_lowercase().cmp(&b.client_name.to_lowercase()),
+                2 => a.progress.total_cmp(&b.progress),
+                3 => a.rate_to_client.cmp(&b.rate_to_client),
+                4 => a.rate_to_peer.cmp(&b.rate_to_peer),
+                5 => a.flag_str.cmp(&b.flag_str),
+                6 => a.is_encrypted.cmp(&b.is_encrypted),
+                7 => a.is_incoming.cmp(&b.is_incoming),
+                8 => a.is_utp.cmp(&b.is_utp),
+                _ => Ordering::Equal
+            }.then(a.address.cmp(&b.address));
+            if self.sort_descending { ordering.reverse() } else { ordering }
+        });
+    }
+
+    // e.g. "██████░░░░  60 %"
+    fn progress_bar(progress: f64) -> String {
+        let filled = ((progress * PROGRESS_BAR_WIDTH as f64).round() as usize).min(PROGRESS_BAR_WIDTH);
+        format!(
+            "{}{} {:>3} %",
+            "\u{2588}".repeat(filled),
+            "\u{2591}".repeat(PROGRESS_BAR_WIDTH - filled),
+            (progress * 100f64).round() as i64
+        )
+    }
+
+    fn indicator(is_set: bool) -> &'static str {
+        if is_set { "\u{2713}" } else { "" }
+    }
+
+    fn table(&self) -> Table<'static> {
+        let rows = self.peers.iter().map(|peer| {
+            Row::new(vec![
+                Cell::from(peer.address.clone()),
+                Cell::from(peer.client_name.clone()),
+                Cell::from(Self::progress_bar(peer.progress)),
+                Cell::from(format!("{}/s", Util::print_bytes(peer.rate_to_client as f64))),
+                Cell::from(format!("{}/s", Util::print_bytes(peer.rate_to_peer as f64))),
+                Cell::from(peer.flag_str.clone()),
+                Cell::from(Self::indicator(peer.is_encrypted)),
+                Cell::from(Self::indicator(peer.is_incoming)),
+                Cell::from(Self::indicator(peer.is_utp)),
+            ])
+        });
+        let header = COLUMNS
+            .iter()
+            .enumerate()
+            .map(|(i, title)| {
+                let title = if i == self.sort_column {
+                    format!("{} {}", title, if self.sort_descending { "\u{25bc}" } else { "\u{25b2}" })
+                } else {
+                    title.to_string()
+                };
+                if i == self.active_column() {
+                    Cell::from(title.underlined().bold())
+                } else {
+                    Cell::from(title)
+                }
+            })
+            .collect::<Row>()
+            .height(1)
+            .bg(Color::Indexed(236)) // https://en.wikipedia.org/wiki/ANSI_escape_code#Colors
+            .fg(Color::Indexed(255));
+        let selected_row_style = Style::default()
+            .add_modifier(Modifier::REVERSED)
+            .fg(Color::Indexed(255)) // https://en.wikipedia.org/wiki/ANSI_escape_code#Colors
+            .bg(Color::Black);
+        Table::new(
+            rows,
+            [
+                Constraint::Length(24),
+                Constraint::Fill(1),
+                Constraint::Length(PROGRESS_BAR_WIDTH as u16 + 6),
+                Constraint::Length(14),
+                Constraint::Length(14),
+                Constraint::Length(8),
+                Constraint::Length(5),
+                Constraint::Length(4),
+                Constraint::Length(5),
+            ],
+        )
+        .header(header)
+        .row_highlight_style(selected_row_style)
+    }
+}
+
+impl KeyEventHandler for PeersTab {
+    /*
+       Returns false if we are done from this screen
+    */
+    fn handle_key_event(&mut self, key_event: KeyEvent, _event: Event) -> bool {
+        if key_event.kind == KeyEventKind::Press {
+            match key_event.code {
+                KeyCode::Char('j') | KeyCode::Down => self.next_row(),
+                KeyCode::Char('k') | KeyCode::Up => self.previous_row(),
+                KeyCode::Char('l') | KeyCode::Right => self.next_column(),
+                KeyCode::Char('h') | KeyCode::Left => self.previous_column(),
+                KeyCode::Char('o') => self.sort_by_active_column(),
+                // leave
+                KeyCode::Esc => return false,
+                _ => {}
+            }
+        }
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn peer(address: &str, client_name: &str, rate_to_client: i64) -> TransmissionTorrentPeer {
+        TransmissionTorrentPeer { address: address.to_string(), client_name: client_name.to_string(), rate_to_client, ..Default::default() }
+    }
+
+    fn tab() -> PeersTab {
+        let mut tab = PeersTab::new();
+        tab.peers = vec![peer("10.0.0.3", "qBittorrent", 100), peer("10.0.0.1", "Transmission", 300), peer("10.0.0.2", "deluge", 100)];
+        tab
+    }
+
+    fn addresses(tab: &PeersTab) -> Vec<&str> {
+        tab.peers.iter().map(|peer| peer.address.as_str()).collect()
+    }
+
+    #[test]
+    fn sorts_by_address_first() {
+        let mut tab = tab();
+        tab.sort_peers();
+        assert_eq!(addresses(&tab), vec!["10.0.0.1", "10.0.0.2", "10.0.0.3"]);
+    }
+
+    #[test]
+    fn sorts_client_names_ignoring_case() {
+        let mut tab = tab();
+        tab.sort_column = 1;
+        tab.sort_peers();
+        assert_eq!(addresses(&tab), vec!["10.0.0.2", "10.0.0.3", "10.0.0.1"]);
+    }
+
+    #[test]
+    fn sorts_ties_by_address_and_reverses_them_too() {
+        let mut tab = tab();
+        tab.sort_column = 3;
+        tab.sort_peers();
+        assert_eq!(addresses(&tab), vec!["10.0.0.2", "10.0.0.3", "10.0.0.1"]);
+        tab.sort_descending = true;
+        tab.sort_peers();
+        assert_eq!(addresses(&tab), vec!["10.0.0.1", "10.0.0.3", "10.0.0.2"]);
+    }
+
+    #[test]
+    fn sorting_the_same_column_again_toggles_the_direction() {
+        let mut tab = tab();
+        tab.table_state.select_column(Some(3));
+        tab.sort_by_active_column();
+        assert_eq!((tab.sort_column, tab.sort_descending), (3, false));
+        tab.sort_by_active_column();
+        assert_eq!((tab.sort_column, tab.sort_descending), (3, true));
+        tab.table_state.select_column(Some(4));
+        tab.sort_by_active_column();
+        assert_eq!((tab.sort_column, tab.sort_descending), (4, false));
+    }
+
+    #[test]
+    fn progress_bar_rounds_to_whole_cells() {
+        assert_eq!(PeersTab::progress_bar(0.0), format!("{}   0 %", "\u{2591}".repeat(10)));
+        assert_eq!(PeersTab::progress_bar(0.64), format!("{}{}  64 %", "\u{2588}".repeat(6), "\u{2591}".repeat(4)));
+        assert_eq!(PeersTab::progress_bar(1.0), format!("{} 100 %", "\u{2588}".repeat(10)));
+    }
+}
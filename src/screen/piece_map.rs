@@ -0,0 +1,87 @@
+use ratatui::buffer::Buffer;
+use ratatui::layout::Rect;
+use ratatui::prelude::{Color, Line, Stylize};
+use ratatui::text::Span;
+use ratatui::widgets::Widget;
+use crate::dto::transmission_dto::TransmissionTorrent;
+
+const COMPLETE_COLOR: Color = Color::Green;
+const PARTIAL_COLOR: Color = Color::Yellow;
+const MISSING_COLOR: Color = Color::DarkGray;
+// drawn over the cell holding the first piece of a file
+const FILE_BOUNDARY_SYMBOL: &str = "\u{2502}";
+
+/*
+    The pieces of a torrent as a grid of colored cells filling the given area, each cell standing for
+    as many consecutive pieces as needed to fit them all (complete, partly complete or missing).
+*/
+pub struct PieceMap {
+    pieces: Vec<bool>,
+    // first piece of every file but the first one, ordered
+    file_boundaries: Vec<usize>
+}
+
+impl PieceMap {
+
+    pub fn new(torrent: &TransmissionTorrent) -> Self {
+        let mut file_boundaries: Vec<usize> = torrent.files
+            .iter()
+            .map(|file| file.begin_piece.max(0) as usize)
+            .filter(|piece| *piece > 0)
+            .collect();
+        file_boundaries.sort_unstable();
+        file_boundaries.dedup();
+        Self { pieces: torrent.completed_pieces(), file_boundaries }
+    }
+
+    // e.g. " 120 of 400 pieces "
+    pub fn title(&self) -> String {
+        format!(" {} of {} pieces ", self.pieces.iter().filter(|piece| **piece).count(), self.pieces.len())
+    }
+
+    pub fn legend() -> Line<'static> {
+        Line::from(vec![
+            Span::from(" "),
+            Span::from("  ").bg(COMPLETE_COLOR),
+            Span::from(" complete  "),
+            Span::from("  ").bg(PARTIAL_COLOR),
+            Span::from(" partly  "),
+            Span::from("  ").bg(MISSING_COLOR),
+            Span::from(" missing  "),
+            Span::from(FILE_BOUNDARY_SYMBOL).bold(),
+            Span::from(" file start "),
+        ])
+    }
+
+    fn has_file_boundary(&self, first_piece: usize, last_piece: usize) -> bool {
+        let i = self.file_boundaries.partition_point(|piece| *piece < first_piece);
+        self.file_boundaries.get(i).is_some_and(|piece| *piece < last_piece)
+    }
+}
+
+impl Widget for PieceMap {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        let cells = area.width as usize * area.height as usize;
+        if self.pieces.is_empty() || cells == 0 {
+            Line::from("No piece information".dim()).render(area, buf);
+            return;
+        }
+
+        let pieces_per_cell = self.pieces.len().div_ceil(cells);
+        self.pieces.chunks(pieces_per_cell).enumerate().for_each(|(cell, pieces)| {
+            let first_piece = cell * pieces_per_cell;
+            let complete = pieces.iter().filter(|piece| **piece).count();
+            let color = if complete == pieces.len() {
+                COMPLETE_COLOR
+            } else if complete > 0 {
+                PARTIAL_COLOR
+            } else {
+                MISSING_COLOR
+            };
+            let symbol = if self.has_file_boundary(first_piece, first_piece + pieces.len()) { FILE_BOUNDARY_SYMBOL } else { " " };
+            let x = area.x + (cell % area.width as usize) as u16;
+            let y = area.y + (cell / area.width as usize) as u16;
+            buf[(x, y)].set_symbol(symbol).set_fg(Color::White).set_bg(color);
+        });
+    }
+}
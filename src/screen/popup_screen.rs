@@ -0,0 +1,195 @@
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use crossterm::event::{Event, KeyCode, KeyEvent, KeyEventKind};
+use ratatui::Frame;
+use ratatui::layout::{Constraint, Flex, Layout, Rect};
+use ratatui::prelude::{Color, Line, Stylize, Text};
+use ratatui::symbols::border;
+use ratatui::widgets::{Block, Clear, Padding, Paragraph, Wrap};
+use crate::app::{EmptyRenderableArgs, KeyEventHandler, Renderable};
+use crate::dto::transmission_dto::TransmissionResponse;
+use crate::error::RustorResult;
+
+const TOAST_TTL: Duration = Duration::from_secs(5);
+const TOAST_WIDTH: u16 = 50;
+const MAX_TOASTS: usize = 5;
+
+#[derive(Clone, Copy, PartialEq)]
+pub enum PopupLevel { Info, Warning, Error }
+
+impl PopupLevel {
+
+    fn color(&self) -> Color {
+        match self {
+            PopupLevel::Info => Color::Green,
+            PopupLevel::Warning => Color::Yellow,
+            PopupLevel::Error => Color::Red
+        }
+    }
+
+    fn title(&self) -> &'static str {
+        match self {
+            PopupLevel::Info => " Info ",
+            PopupLevel::Warning => " Warning ",
+            PopupLevel::Error => " Error "
+        }
+    }
+}
+
+#[derive(Clone)]
+struct Popup {
+    level: PopupLevel,
+    msg: String,
+    // modal popups stay until acknowledged, the others (toasts) expire on their own
+    modal: bool,
+    created_on: Instant
+}
+
+/*
+    Handle given to every screen which wants to report something to the user, all clones share the
+    same queue which is drawn by the `PopupScreen` over whatever screen is active.
+*/
+#[derive(Clone, Default)]
+pub struct Notifier {
+    popups: Arc<Mutex<Vec<Popup>>>
+}
+
+impl Notifier {
+
+    pub fn toast(&self, level: PopupLevel, msg: impl Into<String>) {
+        self.push(level, msg.into(), false);
+    }
+
+    pub fn modal(&self, level: PopupLevel, msg: impl Into<String>) {
+        self.push(level, msg.into(), true);
+    }
+
+    // Success is reported with a toast, failure with a modal popup carrying the error (e.g. the daemon's `result`)
+    pub fn result<T>(&self, result: &RustorResult<T>, success_msg: impl Into<String>) {
+        match result {
+            Ok(_) => self.toast(PopupLevel::Info, success_msg),
+            Err(e) => self.modal(PopupLevel::Error, e.to_string())
+        }
+    }
+
+    // `torrent-add` also succeeds for a torrent the daemon already has, tell the two apart
+    pub fn torrent_added(&self, result: &RustorResult<TransmissionResponse>) {
+        match result {
+            Ok(response) => match (&response.arguments.torrent_added, &response.arguments.torrent_duplicate) {
+                (Some(torrent), _) => self.toast(PopupLevel::Info, format!("Added {}", torrent.name)),
+                (None, Some(torrent)) => self.toast(PopupLevel::Warning, format!("Already added {}", torrent.name)),
+                (None, None) => self.toast(PopupLevel::Info, "Torrent added")
+            },
+            Err(e) => self.modal(PopupLevel::Error, e.to_string())
+        }
+    }
+
+    pub fn has_modal(&self) -> bool {
+        self.popups.lock().unwrap().iter().any(|popup| popup.modal)
+    }
+
+    fn push(&self, level: PopupLevel, msg: String, modal: bool) {
+        self.popups.lock().unwrap().push(Popup { level, msg, modal, created_on: Instant::now() });
+    }
+
+    fn acknowledge(&self) {
+        let mut popups = self.popups.lock().unwrap();
+        if let Some(index) = popups.iter().position(|popup| popup.modal) {
+            popups.remove(index);
+        }
+    }
+
+    // Drop the expired toasts and return what is left
+    fn active(&self) -> Vec<Popup> {
+        let mut popups = self.popups.lock().unwrap();
+        popups.retain(|popup| popup.modal || popup.created_on.elapsed() < TOAST_TTL);
+        popups.clone()
+    }
+}
+
+pub struct PopupScreen {
+    notifier: Notifier
+}
+
+impl PopupScreen {
+
+    pub fn new(notifier: Notifier) -> Self {
+        Self { notifier }
+    }
+
+    fn render_toasts(frame: &mut Frame, toasts: &[&Popup]) {
+        let area = frame.area();
+        let width = TOAST_WIDTH.min(area.width);
+        let mut y = area.y + 1;
+        toasts.iter().rev().take(MAX_TOASTS).for_each(|toast| {
+            let lines = (toast.msg.chars().count() as u16 / width.saturating_sub(4).max(1)) + 1;
+            let height = (lines + 2).min(area.height.saturating_sub(y));
+            if height < 3 {
+                return;
+            }
+            let toast_area = Rect::new(area.right().saturating_sub(width + 1), y, width, height);
+            let block = Block::bordered()
+                .title(toast.level.title().bold())
+                .border_set(border::PLAIN)
+                .fg(toast.level.color());
+            frame.render_widget(Clear, toast_area);
+            frame.render_widget(
+                Paragraph::new(toast.msg.clone().white()).wrap(Wrap { trim: true }).block(block),
+                toast_area
+            );
+            y += height;
+        });
+    }
+
+    fn render_modal(frame: &mut Frame, modal: &Popup, pending: usize) {
+        let [area] = Layout::horizontal([Constraint::Percentage(50)])
+            .flex(Flex::Center)
+            .areas(frame.area());
+        let [area] = Layout::vertical([Constraint::Length(9)])
+            .flex(Flex::Center)
+            .areas(area);
+        let mut bottom_line = String::from(" Close <Enter> ");
+        if pending > 1 {
+            bottom_line = format!(" Close <Enter> ({} more) ", pending - 1);
+        }
+        let block = Block::bordered()
+            .title(Line::from(modal.level.title().bold()).centered())
+            .title_bottom(Line::from(bottom_line.bold()).centered())
+            .padding(Padding::uniform(1))
+            .border_set(border::THICK)
+            .fg(modal.level.color());
+        frame.render_widget(Clear, area);
+        frame.render_widget(
+            Paragraph::new(Text::from(modal.msg.clone()).white()).centered().wrap(Wrap { trim: true }).block(block),
+            area
+        );
+    }
+}
+
+impl Renderable<EmptyRenderableArgs> for PopupScreen {
+    fn render(&mut self, frame: &mut Frame, _args: EmptyRenderableArgs) {
+        let popups = self.notifier.active();
+        let toasts: Vec<&Popup> = popups.iter().filter(|popup| !popup.modal).collect();
+        let modals: Vec<&Popup> = popups.iter().filter(|popup| popup.modal).collect();
+
+        Self::render_toasts(frame, &toasts);
+        if let Some(modal) = modals.first() {
+            Self::render_modal(frame, modal, modals.len());
+        }
+    }
+}
+
+impl KeyEventHandler for PopupScreen {
+    /*
+       Returns false when there are no more popups waiting for acknowledgement
+    */
+    fn handle_key_event(&mut self, key_event: KeyEvent, _event: Event) -> bool {
+        if key_event.kind == KeyEventKind::Press {
+            match key_event.code {
+                KeyCode::Enter | KeyCode::Esc => self.notifier.acknowledge(),
+                _ => {}
+            }
+        }
+        self.notifier.has_modal()
+    }
+}
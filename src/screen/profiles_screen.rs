@@ -0,0 +1,237 @@
+use std::collections::HashMap;
+use std::sync::mpsc::Sender;
+use std::thread;
+use std::time::{Duration, Instant};
+use crossterm::event::{Event, KeyCode, KeyEvent, KeyEventKind};
+use ratatui::Frame;
+use ratatui::layout::Constraint;
+use ratatui::prelude::{Color, Modifier, Style, Text};
+use ratatui::style::Stylize;
+use ratatui::symbols::border;
+use ratatui::text::Line;
+use ratatui::widgets::{Block, Cell, Padding, Row, Table, TableState};
+use crate::app::{EmptyRenderableArgs, KeyEventHandler, Renderable};
+use crate::client::rpc_client::RpcClient;
+use crate::config::{ConfigKeyBindingKey, ConnectionValues};
+use crate::error::RustorResult;
+use crate::event::AppEvent;
+use crate::poller::Poller;
+use crate::screen::key_bindings_block::KeyBindingsBlock;
+use crate::screen::popup_screen::{Notifier, PopupLevel};
+use crate::service::transmission_service::TransmissionService;
+
+// How often the daemons are probed again while the screen is shown
+const PROBE_INTERVAL: Duration = Duration::from_secs(5);
+
+#[derive(Clone)]
+struct Profile {
+    name: String,
+    connection: ConnectionValues,
+    // `None` until the first probe answers
+    status: Option<RustorResult<()>>,
+    is_probing: bool
+}
+
+/*
+    Daemon profiles of the config file with their status, which is probed in the background (so an unreachable daemon
+    never blocks the UI) and published as `AppEvent::ProfileProbe`.
+*/
+pub struct ProfilesScreen {
+    config_key_bindings: HashMap<ConfigKeyBindingKey, char>,
+    poller: Poller,
+    notifier: Notifier,
+    events: Sender<AppEvent>,
+    table_state: TableState,
+    profiles: Vec<Profile>,
+    active_profile: String,
+    probed_on: Option<Instant>
+}
+
+impl ProfilesScreen {
+
+    pub fn new(
+        config_key_bindings: HashMap<ConfigKeyBindingKey, char>,
+        poller: Poller,
+        notifier: Notifier,
+        events: Sender<AppEvent>,
+        profiles: Vec<(String, ConnectionValues)>,
+        active_profile: String
+    ) -> Self {
+        let profiles = profiles
+            .into_iter()
+            .map(|(name, connection)| Profile { name, connection, status: None, is_probing: false })
+            .collect();
+        Self {
+            config_key_bindings,
+            poller,
+            notifier,
+            events,
+            table_state: TableState::default().with_selected(0),
+            profiles,
+            active_profile,
+            probed_on: None
+        }
+    }
+
+    // Probe every daemon on a thread of its own, a profile still being probed is skipped
+    pub fn refresh(&mut self) {
+        self.probed_on = Some(Instant::now());
+        self.profiles.iter_mut().filter(|profile| !profile.is_probing).for_each(|profile| {
+            profile.is_probing = true;
+            let (name, connection, events) = (profile.name.clone(), profile.connection.clone(), self.events.clone());
+            thread::spawn(move || {
+                let result = RpcClient::from_connection(&connection)
+                    .and_then(|rpc_client| TransmissionService::new(rpc_client).probe());
+                let _ = events.send(AppEvent::ProfileProbe { profile: name, result });
+            });
+        });
+    }
+
+    // Keep the status live while the screen is shown
+    pub fn tick(&mut self) {
+        if self.probed_on.is_none_or(|probed_on| probed_on.elapsed() >= PROBE_INTERVAL) {
+            self.refresh();
+        }
+    }
+
+    pub fn update(&mut self, profile: &str, result: RustorResult<()>) {
+        if let Some(profile) = self.profiles.iter_mut().find(|candidate| candidate.name == profile) {
+            profile.status = Some(result);
+            profile.is_probing = false;
+        }
+    }
+
+    pub fn active_profile(&self) -> &str {
+        &self.active_profile
+    }
+
+    pub fn next_row(&mut self) {
+        let i = match self.table_state.selected() {
+            Some(i) if i + 1 < self.profiles.len() => i + 1,
+            Some(i) => i,
+            None => 0
+        };
+        self.table_state.select(Some(i));
+    }
+
+    pub fn previous_row(&mut self) {
+        let i = match self.table_state.selected() {
+            Some(i) => i.saturating_sub(1),
+            None => 0
+        };
+        self.table_state.select(Some(i));
+    }
+
+    fn connect(&mut self) {
+        let Some(profile) = self.profiles.get(self.table_state.selected().unwrap_or(0)) else {
+            return;
+        };
+        match RpcClient::from_connection(&profile.connection) {
+            Ok(rpc_client) => {
+                self.poller.connect(rpc_client);
+                self.active_profile = profile.name.clone();
+                self.notifier.toast(PopupLevel::Info, format!("Connected to {}", profile.connection.url()));
+            }
+            Err(e) => self.notifier.modal(PopupLevel::Error, e.to_string())
+        }
+    }
+
+    fn table(&self) -> Table<'static> {
+        let rows = self.profiles.iter().map(|profile| {
+            let active = if profile.name == self.active_profile { "*" } else { "" };
+            // the error tells an unreachable daemon from rejected credentials or an unexpected answer
+            let status = match &profile.status {
+                Some(Ok(())) => Text::from("\nconnected\n").fg(Color::Green),
+                Some(Err(e)) => Text::from(format!("\n{e}\n")).fg(Color::Red),
+                None => Text::from("\nprobing\n").dim()
+            };
+            Row::new(vec![
+                Cell::from(Text::from(format!("\n{active}\n"))),
+                Cell::from(Text::from(format!("\n{}\n", profile.name))),
+                Cell::from(Text::from(format!("\n{}\n", profile.connection.url()))),
+                Cell::from(status),
+            ]).height(3)
+        });
+        let header = ["", "Profile", "Url", "Status"]
+            .into_iter()
+            .map(Cell::from)
+            .collect::<Row>()
+            .height(1)
+            .bg(Color::Indexed(236)) // https://en.wikipedia.org/wiki/ANSI_escape_code#Colors
+            .fg(Color::Indexed(255));
+        let selected_row_style = Style::default()
+            .add_modifier(Modifier::REVERSED)
+            .fg(Color::Indexed(255)) // https://en.wikipedia.org/wiki/ANSI_escape_code#Colors
+            .bg(Color::Black);
+        Table::new(
+            rows,
+            [
+                Constraint::Length(2),
+                Constraint::Length(20),
+                Constraint::Fill(1),
+                Constraint::Fill(1),
+            ],
+        )
+        .header(header)
+        .row_highlight_style(selected_row_style)
+    }
+}
+
+impl Renderable<EmptyRenderableArgs> for ProfilesScreen {
+    fn render(&mut self, frame: &mut Frame, _args: EmptyRenderableArgs) {
+        let title = Line::from(" Profiles ".bold());
+        let mut key_bindings_block = KeyBindingsBlock::new(self.config_key_bindings.clone());
+        let key_bindings = vec![
+            key_bindings_block.cnf_kb_home(),
+            KeyBindingsBlock::kb_connect(),
+            KeyBindingsBlock::kb_refresh(),
+            KeyBindingsBlock::kb_cancel(),
+            key_bindings_block.cnf_kb_help(),
+            key_bindings_block.cnf_kb_quit()
+        ];
+        let bottom_line = KeyBindingsBlock::key_bindings_as_line(&key_bindings);
+        let block = Block::bordered()
+            .title(title.centered())
+            .title_bottom(bottom_line.centered())
+            .padding(Padding::proportional(1))
+            .border_set(border::THICK);
+        let table = self.table().block(block);
+
+        frame.render_stateful_widget(table, frame.area(), &mut self.table_state);
+    }
+}
+
+impl KeyEventHandler for ProfilesScreen {
+    /*
+       Returns false if we are done from this screen
+    */
+    fn handle_key_event(&mut self, key_event: KeyEvent, _event: Event) -> bool {
+        if key_event.kind == KeyEventKind::Press {
+            match key_event.code {
+                KeyCode::Char('j') | KeyCode::Down => {
+                    self.next_row();
+                    true
+                }
+                KeyCode::Char('k') | KeyCode::Up => {
+                    self.previous_row();
+                    true
+                }
+                KeyCode::Char('r') => {
+                    self.refresh();
+                    true
+                }
+                // submit and leave
+                KeyCode::Enter => {
+                    self.connect();
+                    false
+                }
+                // leave
+                KeyCode::Esc => false,
+                // do not leave (maybe it will change in the future)
+                _ => true,
+            }
+        } else {
+            true
+        }
+    }
+}
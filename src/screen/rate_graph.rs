@@ -0,0 +1,52 @@
+use ratatui::buffer::Buffer;
+use ratatui::layout::{Constraint, Layout, Rect};
+use ratatui::prelude::{Color, Line, Stylize};
+use ratatui::widgets::{Sparkline, Widget};
+use std::time::Instant;
+use crate::rate_history::{RateSamples, HISTORY_WINDOW, SLOT};
+use crate::util::Util;
+
+/*
+    Download and upload rate history side by side, each as a sparkline of one `SLOT` per column under a line with the
+    current rate and the peak of what is drawn.
+*/
+pub struct RateGraph {
+    samples: RateSamples
+}
+
+impl RateGraph {
+
+    pub fn new(samples: RateSamples) -> Self {
+        Self { samples }
+    }
+
+    // One slot per column (as far as the history goes), a slot without sample is left blank
+    fn render_rates(&self, title: &str, rate_of: fn((u64, u64)) -> u64, color: Color, area: Rect, buf: &mut Buffer) {
+        let [title_area, sparkline_area] = Layout::vertical([Constraint::Length(1), Constraint::Fill(1)]).areas(area);
+        let count = (sparkline_area.width as usize).min((HISTORY_WINDOW.as_secs() / SLOT.as_secs()) as usize);
+        let rates: Vec<Option<u64>> = self.samples.slots(Instant::now(), count).into_iter().map(|slot| slot.map(rate_of)).collect();
+        let current = rates.iter().rev().flatten().next().copied().unwrap_or_default();
+        let peak = rates.iter().flatten().max().copied().unwrap_or_default();
+        Line::from(vec![
+            title.bold().fg(color),
+            format!(" {}/s", Util::print_bytes(current as f64)).into(),
+            format!("  peak {}/s over {}", Util::print_bytes(peak as f64), Util::print_duration((SLOT * count as u32).as_secs() as i64)).dim(),
+        ]).render(title_area, buf);
+
+        Sparkline::default()
+            .data(&rates)
+            .max(peak.max(1))
+            .fg(color)
+            .render(sparkline_area, buf);
+    }
+}
+
+impl Widget for RateGraph {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        let [download_area, upload_area] = Layout::horizontal([Constraint::Fill(1), Constraint::Fill(1)])
+            .spacing(2)
+            .areas(area);
+        self.render_rates("\u{2193} Download", |(download, _)| download, Color::Green, download_area, buf);
+        self.render_rates("\u{2191} Upload", |(_, upload)| upload, Color::Cyan, upload_area, buf);
+    }
+}
@@ -1,41 +1,58 @@
 use std::collections::HashMap;
+use std::sync::Arc;
 use crate::app::{KeyEventHandler, Renderable, RenderableArgs};
 use crate::config::{Config, ConfigKeyBindingKey};
 use crate::screen::key_bindings_block::{KeyBindingItem, KeyBindingsBlock};
 use crossterm::event::{Event, KeyCode, KeyEvent, KeyEventKind};
 use ratatui::Frame;
 use ratatui::buffer::Buffer;
-use ratatui::layout::Rect;
+use ratatui::layout::{Margin, Rect};
 use ratatui::prelude::{Line, Stylize, Text, Widget};
 use ratatui::symbols::border;
 use ratatui::widgets::{Block, Paragraph};
 use crate::dto::transmission_dto::TransmissionTorrent;
+use crate::screen::popup_screen::{Notifier, PopupLevel};
 use crate::service::transmission_service::TransmissionService;
+use crate::store::TorrentStore;
 use crate::screen::info_screen::InfoScreen;
+use crate::util::Util;
 
 #[derive(Clone)]
 pub struct ReannScreen {
     config_key_bindings: HashMap<ConfigKeyBindingKey, char>,
-    selected_row_index: usize,
+    transmission_service: Arc<TransmissionService>,
+    notifier: Notifier,
+    torrent_store: TorrentStore,
+    torrent_ids: Vec<i64>
 }
 
 impl ReannScreen {
-    pub fn new(config_key_bindings: HashMap<ConfigKeyBindingKey, char>) -> Self {
-        Self { config_key_bindings, selected_row_index: 0 }
+    pub fn new(
+        config_key_bindings: HashMap<ConfigKeyBindingKey, char>,
+        transmission_service: Arc<TransmissionService>,
+        notifier: Notifier,
+        torrent_store: TorrentStore
+    ) -> Self {
+        Self { config_key_bindings, transmission_service, notifier, torrent_store, torrent_ids: vec![] }
+    }
+
+    // Every affected torrent still known to the daemon
+    fn torrents(&self) -> Vec<TransmissionTorrent> {
+        self.torrent_ids.iter().filter_map(|torrent_id| self.torrent_store.get(*torrent_id)).collect()
     }
 }
 
 pub struct ReannScreenArgs {
-    selected_row_index: usize
+    torrent_ids: Vec<i64>
 }
 
 impl ReannScreenArgs {
-    pub fn new(selected_row_index: usize) -> Self {
-        Self { selected_row_index }
+    pub fn new(torrent_ids: Vec<i64>) -> Self {
+        Self { torrent_ids }
     }
 
-    pub fn get_selected_row_index(&self) -> usize {
-        self.selected_row_index
+    pub fn get_torrent_ids(&self) -> Vec<i64> {
+        self.torrent_ids.clone()
     }
 }
 
@@ -43,7 +60,7 @@ impl RenderableArgs for ReannScreenArgs {}
 
 impl Renderable<ReannScreenArgs> for ReannScreen {
     fn render(&mut self, frame: &mut Frame, args: ReannScreenArgs) {
-        self.selected_row_index = args.get_selected_row_index();
+        self.torrent_ids = args.get_torrent_ids();
         frame.render_widget(self.clone(), frame.area());
     }
 }
@@ -53,12 +70,11 @@ impl Widget for ReannScreen {
     where
         Self: Sized,
     {
-        let torrents: Vec<TransmissionTorrent> = TransmissionService::torrent_list()
-            .arguments
-            .torrents;
-        let info = torrents.get(self.selected_row_index).unwrap();
-
-        let title = Line::from(" Reannounce torrent ".bold());
+        let torrents = self.torrents();
+        let title = match torrents.len() {
+            1 => Line::from(" Reannounce torrent ".bold()),
+            n => Line::from(format!(" Reannounce {} torrents ", n).bold())
+        };
         let mut key_bindings_block = KeyBindingsBlock::new(self.config_key_bindings.clone());
         let key_bindings = vec![
             key_bindings_block.cnf_kb_home(),
@@ -73,15 +89,16 @@ impl Widget for ReannScreen {
             .title(title.centered())
             .title_bottom(bottom_line.centered())
             .border_set(border::THICK);
-        let body = Text::from(vec![
-            Line::from(vec!["".into()]),
-            Line::from(vec![info.name.clone().into()]),
-            Line::from(vec!["".into()]),
-        ]);
-        Paragraph::new(body)
+        let mut lines = vec![];
+        if torrents.is_empty() {
+            lines.push(Line::from(vec!["No torrent selected".red()]));
+        }
+        lines.extend(torrents.iter().map(|torrent| Line::from(vec![torrent.name.clone().into()])));
+        let list_area = block.inner(area).inner(Margin::new(0, 1));
+        block.render(area, buf);
+        Paragraph::new(Text::from(Util::cap_lines(lines, list_area.height as usize)))
             .centered()
-            .block(block)
-            .render(area, buf);
+            .render(list_area, buf);
     }
 }
 
@@ -91,10 +108,15 @@ impl KeyEventHandler for ReannScreen {
             match key_event.code {
                 // submit and leave
                 KeyCode::Enter => {
-                    let torrents: Vec<TransmissionTorrent> = TransmissionService::torrent_list()
-                        .arguments
-                        .torrents;
-                    TransmissionService::torrent_reannounce(torrents[self.selected_row_index].id.to_string());
+                    let torrents = self.torrents();
+                    if !torrents.is_empty() {
+                        let ids: Vec<i64> = torrents.iter().map(|torrent| torrent.id).collect();
+                        let result = self.transmission_service.torrent_reannounce(&ids);
+                        match torrents.as_slice() {
+                            [torrent] => self.notifier.result(&result, format!("Reannounced {}", torrent.name)),
+                            _ => self.notifier.result(&result, format!("Reannounced {} torrents", torrents.len()))
+                        }
+                    }
                     false
                 }
                 // leave
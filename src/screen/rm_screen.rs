@@ -1,41 +1,105 @@
 use std::collections::HashMap;
+use std::sync::Arc;
 use crate::app::{KeyEventHandler, Renderable, RenderableArgs};
 use crate::config::{Config, ConfigKeyBindingKey};
 use crate::screen::key_bindings_block::KeyBindingsBlock;
 use crossterm::event::{Event, KeyCode, KeyEvent, KeyEventKind};
 use ratatui::Frame;
 use ratatui::buffer::Buffer;
-use ratatui::layout::Rect;
+use ratatui::layout::{Constraint, Layout, Rect};
 use ratatui::prelude::{Line, Stylize, Text, Widget};
 use ratatui::symbols::border;
 use ratatui::widgets::{Block, Paragraph};
 use crate::dto::transmission_dto::TransmissionTorrent;
+use crate::screen::popup_screen::{Notifier, PopupLevel};
 use crate::service::transmission_service::TransmissionService;
+use crate::store::TorrentStore;
 use crate::screen::reann_screen::ReannScreen;
+use crate::util::Util;
+use tui_input::Input;
+use tui_input::backend::crossterm::EventHandler;
+
+// Word to type before data gets deleted
+const CONFIRMATION: &str = "delete";
 
 #[derive(Clone)]
 pub struct RmScreen {
     config_key_bindings: HashMap<ConfigKeyBindingKey, char>,
-    selected_row_index: usize,
+    transmission_service: Arc<TransmissionService>,
+    notifier: Notifier,
+    torrent_store: TorrentStore,
+    torrent_ids: Vec<i64>,
+    // false: remove torrent only, true: remove torrent and delete data
+    delete_local_data: bool,
+    // typed confirmation needed when deleting data
+    confirmation: Input
 }
 
 impl RmScreen {
-    pub fn new(config_key_bindings: HashMap<ConfigKeyBindingKey, char>) -> Self {
-        Self { config_key_bindings, selected_row_index: 0 }
+    pub fn new(
+        config_key_bindings: HashMap<ConfigKeyBindingKey, char>,
+        transmission_service: Arc<TransmissionService>,
+        notifier: Notifier,
+        torrent_store: TorrentStore
+    ) -> Self {
+        Self {
+            config_key_bindings,
+            transmission_service,
+            notifier,
+            torrent_store,
+            torrent_ids: vec![],
+            delete_local_data: false,
+            confirmation: Input::default()
+        }
+    }
+
+    // Every affected torrent still known to the daemon
+    fn torrents(&self) -> Vec<TransmissionTorrent> {
+        self.torrent_ids.iter().filter_map(|torrent_id| self.torrent_store.get(*torrent_id)).collect()
+    }
+
+    fn is_confirmed(&self) -> bool {
+        !self.delete_local_data || self.confirmation.value() == CONFIRMATION
+    }
+
+    // Back to the safe option, also on every visit since the screen can be left with any global key
+    pub fn reset(&mut self) {
+        self.delete_local_data = false;
+        self.confirmation.reset();
+    }
+
+    fn remove(&mut self) {
+        let torrents = self.torrents();
+        if torrents.is_empty() {
+            return;
+        }
+        let ids: Vec<i64> = torrents.iter().map(|torrent| torrent.id).collect();
+        let result = self.transmission_service.torrent_remove(&ids, self.delete_local_data);
+        let done = if self.delete_local_data { "Removed and deleted" } else { "Removed" };
+        match torrents.as_slice() {
+            [torrent] => self.notifier.result(&result, format!("{} {}", done, torrent.name)),
+            _ => self.notifier.result(&result, format!("{} {} torrents", done, torrents.len()))
+        }
+    }
+
+    fn option_line(&self, label: &'static str, is_delete_option: bool) -> Line<'static> {
+        let mark = if self.delete_local_data == is_delete_option { "(x) " } else { "( ) " };
+        let line = Line::from(vec![mark.into(), label.into()]);
+        if self.delete_local_data == is_delete_option { line.bold() } else { line }
     }
 }
 
 pub struct RmScreenArgs {
-    selected_row_index: usize
+    torrent_ids: Vec<i64>
 }
 
 impl RmScreenArgs {
-    pub fn new(selected_row_index: usize) -> Self {
-        Self { selected_row_index }
+    pub fn new(torrent_ids: Vec<i64>) -> Self {
+        Self { torrent_ids }
     }
 
-    pub fn get_selected_row_index(&self) -> usize {
-        self.selected_row_index
+    pub fn get_torrent_ids(&self) -> Vec<i64> {
+        self.torrent_ids.clone()
     }
 }
 
@@ -43,7 +107,7 @@ impl RenderableArgs for RmScreenArgs {}
 
 impl Renderable<RmScreenArgs> for RmScreen {
     fn render(&mut self, frame: &mut Frame, args: RmScreenArgs) {
-        self.selected_row_index = args.get_selected_row_index();
+        self.torrent_ids = args.get_torrent_ids();
         frame.render_widget(self.clone(), frame.area());
     }
 }
@@ -53,52 +117,101 @@ impl Widget for RmScreen {
     where
         Self: Sized,
     {
-        let torrents: Vec<TransmissionTorrent> = TransmissionService::torrent_list()
-            .arguments
-            .torrents;
-        let info = torrents.get(self.selected_row_index).unwrap();
-
-        let title = Line::from(" Remove torrent ".bold());
+        let torrents = self.torrents();
+        let title = match torrents.len() {
+            1 => Line::from(" Remove torrent ".bold()),
+            n => Line::from(format!(" Remove {} torrents ", n).bold())
+        };
         let mut key_bindings_block = KeyBindingsBlock::new(self.config_key_bindings.clone());
         let key_bindings = vec![
             key_bindings_block.cnf_kb_home(),
             key_bindings_block.cnf_kb_add(),
             key_bindings_block.cnf_kb_search(),
+            KeyBindingsBlock::kb_option(),
             KeyBindingsBlock::kb_cancel(),
             key_bindings_block.cnf_kb_help(),
             key_bindings_block.cnf_kb_quit()
         ];
         let bottom_line = KeyBindingsBlock::key_bindings_as_line(&key_bindings);
-        let body = Text::from(vec![
+        let mut lines = vec![];
+        if torrents.is_empty() {
+            lines.push(Line::from(vec!["No torrent selected".red()]));
+        }
+        lines.extend(torrents.iter().map(|torrent| Line::from(vec![
+            torrent.name.clone().into(),
+            format!("  {} in {}", torrent.downloaded(), torrent.download_dir).gray()
+        ])));
+
+        // the options always keep their place, however many torrents are listed above them
+        let on_disk: i64 = torrents.iter().map(|torrent| torrent.size_when_done - torrent.left_until_done).sum();
+        let mut options = vec![
             Line::from(vec!["".into()]),
-            Line::from(vec![info.name.clone().into()]),
+            Line::from(vec!["Total on disk: ".bold(), Util::print_bytes(on_disk as f64).into()]),
             Line::from(vec!["".into()]),
-        ]);
+            self.option_line("Remove torrent only", false),
+            self.option_line("Remove torrent and delete data", true)
+        ];
+        if self.delete_local_data {
+            options.push(Line::from(vec!["".into()]));
+            options.push(Line::from(vec![
+                format!("Type `{}` to confirm: ", CONFIRMATION).red().bold(),
+                format!("{}_", self.confirmation.value()).into()
+            ]));
+        }
         let block = Block::bordered()
             .title(title.centered())
             .title_bottom(bottom_line.centered())
             .border_set(border::THICK);
-        Paragraph::new(body)
+        let inner_area = block.inner(area);
+        let options_height = options.len() as u16;
+        let list_height = (lines.len() as u16).min(inner_area.height.saturating_sub(options_height + 1));
+        let [_, list_area, options_area] = Layout::vertical([
+            Constraint::Length(1),
+            Constraint::Length(list_height),
+            Constraint::Length(options_height)
+        ]).areas(inner_area);
+        block.render(area, buf);
+        Paragraph::new(Text::from(Util::cap_lines(lines, list_area.height as usize)))
+            .centered()
+            .render(list_area, buf);
+        Paragraph::new(Text::from(options))
             .centered()
-            .block(block)
-            .render(area, buf);
+            .render(options_area, buf);
     }
 }
 
 impl KeyEventHandler for RmScreen {
+    /*
+       Returns false if we are done from this screen
+    */
     fn handle_key_event(&mut self, key_event: KeyEvent, event: Event) -> bool {
         if key_event.kind == KeyEventKind::Press {
             match key_event.code {
-                // submit and leave
+                // switch between the two options
+                KeyCode::Tab | KeyCode::BackTab | KeyCode::Up | KeyCode::Down => {
+                    self.delete_local_data = !self.delete_local_data;
+                    self.confirmation.reset();
+                    true
+                }
+                // submit and leave (unless deleting data without confirming it)
                 KeyCode::Enter => {
-                    let torrents: Vec<TransmissionTorrent> = TransmissionService::torrent_list()
-                        .arguments
-                        .torrents;
-                    TransmissionService::torrent_remove(torrents[self.selected_row_index].id.to_string());
+                    if !self.is_confirmed() {
+                        return true;
+                    }
+                    self.remove();
+                    self.reset();
                     false
                 }
                 // leave
-                KeyCode::Esc => false,
+                KeyCode::Esc => {
+                    self.reset();
+                    false
+                }
+                // let confirmation input handle it
+                _ if self.delete_local_data => {
+                    self.confirmation.handle_event(&event);
+                    true
+                }
                 // do not leave (maybe it will change in the future)
                 _ => true,
             }
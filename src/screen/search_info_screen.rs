@@ -15,11 +15,12 @@ use crate::config::{Config, ConfigKeyBindingKey};
 use crate::dto::torrent_dto::{PirateBayTorrentFile, SearchTorrent, TorrentSource};
 use crate::dto::transmission_dto::TransmissionTorrent;
 use crate::mapper::Mapper;
-use crate::screen::info_screen::{InfoScreen, InfoScreenArgs};
+use crate::screen::info_screen::InfoScreen;
 use crate::screen::key_bindings_block::KeyBindingsBlock;
 use crate::service::torrent_service::TorrentService;
 use crate::service::transmission_service::TransmissionService;
 use crate::util::Util;
+use crate::error::RustorResult;
 
 pub struct SearchInfoScreen {
     config_key_bindings: HashMap<ConfigKeyBindingKey, char>,
@@ -43,16 +44,16 @@ impl SearchInfoScreen {
 }
 
 pub struct SearchInfoScreenArgs {
-    selected_torrent: SearchTorrent
+    selected_torrent: RustorResult<SearchTorrent>
 }
 
 impl SearchInfoScreenArgs {
 
-    pub fn new(selected_torrent: SearchTorrent) -> Self {
+    pub fn new(selected_torrent: RustorResult<SearchTorrent>) -> Self {
         Self { selected_torrent }
     }
 
-    pub fn get_selected_torrent(&self) -> &SearchTorrent {
+    pub fn get_selected_torrent(&self) -> &RustorResult<SearchTorrent> {
         &self.selected_torrent
     }
 }
@@ -61,7 +62,14 @@ impl RenderableArgs for SearchInfoScreenArgs {}
 
 impl Renderable<SearchInfoScreenArgs> for SearchInfoScreen {
     fn render(&mut self, frame: &mut Frame, args: SearchInfoScreenArgs) {
-        self.selected_row_torrent = args.get_selected_torrent().clone();
+        self.selected_row_torrent = match args.get_selected_torrent() {
+            Ok(torrent) => torrent.clone(),
+            Err(e) => {
+                let block = Block::bordered().padding(Padding::uniform(1));
+                frame.render_widget(Paragraph::new(e.to_string().red()).centered().block(block), frame.area());
+                return;
+            }
+        };
         let scroll_view_height = 30;
         let buf = frame.buffer_mut();
 
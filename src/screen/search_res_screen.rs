@@ -20,6 +20,8 @@ use crate::service::transmission_service::TransmissionService;
 use crate::screen::rm_screen::RmScreen;
 use crate::screen::key_bindings_block::{KeyBindingItem, KeyBindingsBlock};
 use crate::mapper::Mapper;
+use crate::error::{RustorError, RustorResult};
+use crate::screen::popup_screen::Notifier;
 use crate::util::Util;
 
 #[derive(Default, Clone)]
@@ -33,16 +35,25 @@ struct State {
 pub struct SearchResScreen {
     config_key_bindings: HashMap<ConfigKeyBindingKey, char>,
     torrent_service_arc: Arc<TorrentService>,
+    transmission_service: Arc<TransmissionService>,
+    notifier: Notifier,
     table_state: TableState,
     state: State
 }
 
 impl SearchResScreen {
 
-    pub fn new(config_key_bindings: HashMap<ConfigKeyBindingKey, char>, torrent_service_arc: Arc<TorrentService>) -> Self {
+    pub fn new(
+        config_key_bindings: HashMap<ConfigKeyBindingKey, char>,
+        torrent_service_arc: Arc<TorrentService>,
+        transmission_service: Arc<TransmissionService>,
+        notifier: Notifier
+    ) -> Self {
         Self {
             config_key_bindings,
             torrent_service_arc,
+            transmission_service,
+            notifier,
             table_state: TableState::default().with_selected(0),
             state: State::default()
         }
@@ -89,31 +100,34 @@ impl SearchResScreen {
         self.table_state.select_previous_column();
     }
 
-    pub fn active_row_torrent(&mut self) -> SearchTorrent {
+    pub fn active_row_torrent(&mut self) -> RustorResult<SearchTorrent> {
         let cur_sel_id = self.table_state.selected().unwrap_or(0);
         if  !self.state.selected_row_torrent.name.is_empty() && // We have to check the `name` because in case of TorrentsCsv we do not get any info because they do not have an API for this.
             self.state.row_index_last_used_for_fetching_torrent == cur_sel_id {
-            return self.state.selected_row_torrent.clone()
+            return Ok(self.state.selected_row_torrent.clone())
         }
 
         let cur_sel_index = self.table_state.selected().unwrap_or(0);
-        self.state.row_index_last_used_for_fetching_torrent = cur_sel_index.clone();
-        let active_torrent = self.state.torrents[cur_sel_index].clone();
-        let active_torrent_id = active_torrent.id.parse().unwrap();
+        let active_torrent = self.state.torrents.get(cur_sel_index).cloned().unwrap_or_default();
 
-        self.state.selected_row_torrent = match active_torrent.source {
+        let selected_row_torrent = match active_torrent.source {
 
             // Get extra info from PirateBay
             TorrentSource::PirateBay => {
+                let active_torrent_id = active_torrent.id
+                    .parse()
+                    .map_err(|_| RustorError::Parse(format!("invalid PirateBay torrent id `{}`", active_torrent.id)))?;
                 Mapper::pirate_bay_torrent_info_and_files_result_to_search_torrent(
                     &self.torrent_service_arc.torrent_info_pirate_bay(active_torrent_id),
                     &self.torrent_service_arc.torrent_files_pirate_bay(active_torrent_id)
-                )
+                )?
             }
             _ => active_torrent
         };
+        self.state.row_index_last_used_for_fetching_torrent = cur_sel_index;
+        self.state.selected_row_torrent = selected_row_torrent;
 
-        self.state.selected_row_torrent.clone()
+        Ok(self.state.selected_row_torrent.clone())
     }
 
     fn table(self, torrents: &Vec<SearchTorrent>) -> Table<'static> {
@@ -179,7 +193,11 @@ impl SearchResScreen {
     }
 
     fn download(&mut self) {
-        TransmissionService::torrent_add(format!("magnet:?xt=urn:btih:{}", self.active_row_torrent().info_hash.as_str()));
+        let Some(torrent) = self.state.torrents.get(self.table_state.selected().unwrap_or(0)) else {
+            return;
+        };
+        let magnet_link = format!("magnet:?xt=urn:btih:{}", torrent.info_hash.as_str());
+        self.notifier.torrent_added(&self.transmission_service.torrent_add(magnet_link));
     }
 }
 
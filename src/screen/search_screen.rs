@@ -17,6 +17,8 @@ use crate::dto::torrent_dto::{SearchTorrent, TorrentSource};
 use crate::service::torrent_service::TorrentService;
 use crate::screen::search_res_screen::SearchResScreen;
 use crate::mapper::Mapper;
+use crate::error::RustorResult;
+use crate::screen::popup_screen::{Notifier, PopupLevel};
 use crate::screen::key_bindings_block::KeyBindingsBlock;
 
 #[derive(Default)]
@@ -35,18 +37,22 @@ pub struct SearchScreen {
     config_key_bindings: HashMap<ConfigKeyBindingKey, char>,
     torrent_service: Arc<TorrentService>,
     input: Input,
-    state: State,
-    error_msg: String
+    notifier: Notifier,
+    state: State
 }
 
 impl SearchScreen {
-    pub fn new(config_key_bindings: HashMap<ConfigKeyBindingKey, char>, torrent_service: Arc<TorrentService>) -> Self {
+    pub fn new(
+        config_key_bindings: HashMap<ConfigKeyBindingKey, char>,
+        torrent_service: Arc<TorrentService>,
+        notifier: Notifier
+    ) -> Self {
         Self {
             config_key_bindings,
             torrent_service,
             input: Input::default(),
-            state: State::default(),
-            error_msg: String::new()
+            notifier,
+            state: State::default()
         }
     }
     
@@ -110,46 +116,37 @@ impl KeyEventHandler for SearchScreen {
             match key_event.code {
                 // submit and leave
                 KeyCode::Enter => {
-                    let pirate_bay_result = match self.torrent_service.search_pirate_bay(self.input.value()) {
-                        Ok(pirate_bay_result) => {
-                            let result = pirate_bay_result[..min(pirate_bay_result.len(), 20)]
-                                .iter()
-                                .map(|torrent| Mapper::pirate_bay_list_torrent_to_search_torrent(torrent))
-                                .collect();
-                            Ok(result)
-                        },
-                        Err(e) => Err(e)
-                    };
-                    let torrents_csv_result = match self.torrent_service.search_torrents_csv(self.input.value()) {
-                        Ok(torrents_csv_result) => {
-                            let result = torrents_csv_result
-                                .iter()
-                                .map(|torrent| Mapper::torrents_csv_torrent_to_search_torrent(torrent))
-                                .collect();
-                            Ok(result)
-                        },
-                        Err(e) => Err(e)
-                    };
-
-                    self.input.reset();
+                    let pirate_bay_result: RustorResult<Vec<SearchTorrent>> = self.torrent_service
+                        .search_pirate_bay(self.input.value())
+                        .and_then(|pirate_bay_result| pirate_bay_result[..min(pirate_bay_result.len(), 20)]
+                            .iter()
+                            .map(Mapper::pirate_bay_list_torrent_to_search_torrent)
+                            .collect());
+                    let torrents_csv_result: RustorResult<Vec<SearchTorrent>> = self.torrent_service
+                        .search_torrents_csv(self.input.value())
+                        .and_then(|torrents_csv_result| torrents_csv_result
+                            .iter()
+                            .map(Mapper::torrents_csv_torrent_to_search_torrent)
+                            .collect());
 
                     self.state.results = match (&pirate_bay_result, &torrents_csv_result) {
                         (Err(_), Err(_)) => {
-                            // TODO show error message (piratebay/torrentscsv failed)
+                            // stay on this screen so the search can be retried
                             let piratebay_error = pirate_bay_result.err().unwrap().to_string();
                             let torrentscsv_error = torrents_csv_result.err().unwrap().to_string();
+                            self.notifier.modal(PopupLevel::Error, format!("{}\n{}", piratebay_error, torrentscsv_error));
 
-                            vec![]
+                            return true;
                         },
                         (Err(_), Ok(_)) => {
-                            // TODO show error message (piratebay failed)
-                            let piratebay_error = pirate_bay_result.err().unwrap();
+                            let piratebay_error = pirate_bay_result.err().unwrap().to_string();
+                            self.notifier.toast(PopupLevel::Warning, piratebay_error);
 
                             torrents_csv_result.unwrap()
                         },
                         (Ok(_), Err(_)) => {
-                            // TODO show error message (torrentscsv failed)
                             let torrentscsv_error = torrents_csv_result.err().unwrap().to_string();
+                            self.notifier.toast(PopupLevel::Warning, torrentscsv_error);
 
                             pirate_bay_result.unwrap()
                         },
@@ -159,6 +156,7 @@ impl KeyEventHandler for SearchScreen {
                             result
                         }
                     };
+                    self.input.reset();
                     false
                 }
                 // leave
@@ -0,0 +1,153 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+use crossterm::event::{Event, KeyEvent};
+use ratatui::Frame;
+use ratatui::prelude::{Line, Stylize};
+use ratatui::symbols::border;
+use ratatui::widgets::{Block, Padding, Paragraph};
+use crate::app::{EmptyRenderableArgs, KeyEventHandler, Renderable};
+use crate::config::ConfigKeyBindingKey;
+use crate::error::RustorResult;
+use crate::screen::form::{field, FieldKind, Form, FormAction, Section, COUNT};
+use crate::screen::key_bindings_block::KeyBindingsBlock;
+use crate::screen::popup_screen::Notifier;
+use crate::service::transmission_service::TransmissionService;
+
+// See `session-set` in https://github.com/transmission/transmission/blob/main/docs/rpc-spec.md
+const SECTIONS: &[Section] = &[
+    ("Speed limits", &[
+        field("speed-limit-down-enabled", "Limit download", FieldKind::Bool),
+        field("speed-limit-down", "Download limit (kB/s)", COUNT),
+        field("speed-limit-up-enabled", "Limit upload", FieldKind::Bool),
+        field("speed-limit-up", "Upload limit (kB/s)", COUNT),
+    ]),
+    ("Alternative speed limits", &[
+        field("alt-speed-enabled", "Enabled", FieldKind::Bool),
+        field("alt-speed-down", "Download limit (kB/s)", COUNT),
+        field("alt-speed-up", "Upload limit (kB/s)", COUNT),
+        field("alt-speed-time-enabled", "Scheduled", FieldKind::Bool),
+        field("alt-speed-time-begin", "From", FieldKind::Time),
+        field("alt-speed-time-end", "To", FieldKind::Time),
+        field("alt-speed-time-day", "Days (Sun=1 Mon=2 Tue=4 .. Sat=64)", FieldKind::Int { min: 0, max: 127 }),
+    ]),
+    ("Peers", &[
+        field("peer-limit-global", "Global peer limit", COUNT),
+        field("peer-limit-per-torrent", "Peer limit per torrent", COUNT),
+        field("encryption", "Encryption", FieldKind::Choice(&["required", "preferred", "tolerated"])),
+        field("dht-enabled", "DHT", FieldKind::Bool),
+        field("pex-enabled", "PEX", FieldKind::Bool),
+        field("lpd-enabled", "Local peer discovery", FieldKind::Bool),
+        field("utp-enabled", "uTP", FieldKind::Bool),
+    ]),
+    ("Directories", &[
+        field("download-dir", "Download directory", FieldKind::Text),
+        field("incomplete-dir-enabled", "Use incomplete directory", FieldKind::Bool),
+        field("incomplete-dir", "Incomplete directory", FieldKind::Text),
+    ]),
+    ("Seeding", &[
+        field("seedRatioLimited", "Stop at ratio", FieldKind::Bool),
+        field("seedRatioLimit", "Ratio", FieldKind::Float),
+        field("idle-seeding-limit-enabled", "Stop when idle", FieldKind::Bool),
+        field("idle-seeding-limit", "Idle limit (minutes)", COUNT),
+    ]),
+    ("Queue", &[
+        field("download-queue-enabled", "Limit downloads", FieldKind::Bool),
+        field("download-queue-size", "Active downloads", COUNT),
+        field("seed-queue-enabled", "Limit seeds", FieldKind::Bool),
+        field("seed-queue-size", "Active seeds", COUNT),
+    ]),
+    ("Network", &[
+        field("peer-port", "Peer port", FieldKind::Int { min: 1, max: 65535 }),
+        field("peer-port-random-on-start", "Random port on start", FieldKind::Bool),
+        field("port-forwarding-enabled", "Port forwarding (UPnP / NAT-PMP)", FieldKind::Bool),
+    ]),
+];
+
+/*
+    Form over the daemon settings, loaded with `session-get` when the screen opens.
+    Edits stay local until they are previewed (old and new value of every change) and applied with `session-set`.
+*/
+pub struct SettingsScreen {
+    config_key_bindings: HashMap<ConfigKeyBindingKey, char>,
+    transmission_service: Arc<TransmissionService>,
+    notifier: Notifier,
+    form: Form,
+    // only the error, the values live in the form
+    loaded: RustorResult<()>
+}
+
+impl SettingsScreen {
+
+    pub fn new(
+        config_key_bindings: HashMap<ConfigKeyBindingKey, char>,
+        transmission_service: Arc<TransmissionService>,
+        notifier: Notifier
+    ) -> Self {
+        Self {
+            config_key_bindings,
+            transmission_service,
+            form: Form::new(SECTIONS, notifier.clone()),
+            notifier,
+            loaded: Ok(())
+        }
+    }
+
+    // Fetch the current settings, dropping every edit
+    pub fn load(&mut self) {
+        let values = self.transmission_service.session_get_values(&self.form.keys());
+        self.loaded = values.as_ref().map(|_| ()).map_err(|e| e.clone());
+        self.form.reset(values.unwrap_or_default());
+    }
+
+    fn apply(&mut self) {
+        let result = self.transmission_service.session_set(self.form.edits());
+        self.notifier.result(&result, format!("Saved {} settings", self.form.edit_count()));
+        if result.is_ok() {
+            self.load();
+        } else {
+            self.form.browse();
+        }
+    }
+}
+
+impl Renderable<EmptyRenderableArgs> for SettingsScreen {
+    fn render(&mut self, frame: &mut Frame, _args: EmptyRenderableArgs) {
+        let mut key_bindings_block = KeyBindingsBlock::new(self.config_key_bindings.clone());
+        let mut key_bindings = self.form.key_bindings();
+        if !self.form.is_previewing() {
+            key_bindings.extend([key_bindings_block.cnf_kb_home(), KeyBindingsBlock::kb_cancel(), key_bindings_block.cnf_kb_quit()]);
+        }
+        let mut title = " Daemon settings ".to_string();
+        if self.form.edit_count() > 0 {
+            title = format!("{}({} changed) ", title, self.form.edit_count());
+        }
+        let block = Block::bordered()
+            .title(Line::from(title.bold()).centered())
+            .title_bottom(KeyBindingsBlock::key_bindings_as_line(&key_bindings).centered())
+            .padding(Padding::proportional(1))
+            .border_set(border::THICK);
+        let area = block.inner(frame.area());
+        frame.render_widget(block, frame.area());
+
+        if let Err(e) = &self.loaded {
+            frame.render_widget(Paragraph::new(e.to_string().red()).centered(), area);
+            return;
+        }
+        self.form.render(frame, area);
+    }
+}
+
+impl KeyEventHandler for SettingsScreen {
+    /*
+       Returns false if we are done from this screen
+    */
+    fn handle_key_event(&mut self, key_event: KeyEvent, event: Event) -> bool {
+        match self.form.handle_key_event(key_event, event) {
+            FormAction::Apply => self.apply(),
+            FormAction::Reload => self.load(),
+            FormAction::Leave => return false,
+            FormAction::None => {}
+        }
+        true
+    }
+}
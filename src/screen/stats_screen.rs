@@ -0,0 +1,125 @@
+use std::collections::HashMap;
+use crossterm::event::{Event, KeyCode, KeyEvent, KeyEventKind};
+use ratatui::Frame;
+use ratatui::layout::{Constraint, Layout};
+use ratatui::prelude::{Color, Stylize};
+use ratatui::symbols::border;
+use ratatui::text::Line;
+use ratatui::widgets::{Block, Cell, Padding, Paragraph, Row, Table};
+use crate::app::{EmptyRenderableArgs, KeyEventHandler, Renderable};
+use crate::config::ConfigKeyBindingKey;
+use crate::dto::transmission_dto::TransmissionStats;
+use crate::error::RustorResult;
+use crate::poller::SessionStats;
+use crate::screen::key_bindings_block::KeyBindingsBlock;
+use crate::util::Util;
+
+// Daemon wide numbers, kept up to date by the `Poller` while the screen is shown
+pub struct StatsScreen {
+    config_key_bindings: HashMap<ConfigKeyBindingKey, char>,
+    session_stats: Option<RustorResult<SessionStats>>
+}
+
+impl StatsScreen {
+
+    pub fn new(config_key_bindings: HashMap<ConfigKeyBindingKey, char>) -> Self {
+        Self { config_key_bindings, session_stats: None }
+    }
+
+    pub fn update(&mut self, session_stats: RustorResult<SessionStats>) {
+        self.session_stats = Some(session_stats);
+    }
+
+    fn ratio(stats: &TransmissionStats) -> String {
+        stats.ratio().map(|ratio| format!("{:.2}", ratio)).unwrap_or("-".to_string())
+    }
+
+    fn totals_table(session_stats: &SessionStats) -> Table<'static> {
+        let (current, cumulative) = (&session_stats.stats.current_stats, &session_stats.stats.cumulative_stats);
+        let row = |title: &str, value: fn(&TransmissionStats) -> String| {
+            Row::new(vec![Cell::from(title.to_string().bold()), Cell::from(value(current)), Cell::from(value(cumulative))])
+        };
+        let rows = vec![
+            row("Uploaded", |stats| Util::print_bytes(stats.uploaded_bytes as f64)),
+            row("Downloaded", |stats| Util::print_bytes(stats.downloaded_bytes as f64)),
+            row("Ratio", Self::ratio),
+            row("Files added", |stats| stats.files_added.to_string()),
+            row("Time active", |stats| Util::print_duration(stats.seconds_active)),
+            row("Sessions", |stats| stats.session_count.to_string()),
+        ];
+        let header = ["", "This session", "Total"]
+            .into_iter()
+            .map(Cell::from)
+            .collect::<Row>()
+            .height(1)
+            .bg(Color::Indexed(236)) // https://en.wikipedia.org/wiki/ANSI_escape_code#Colors
+            .fg(Color::Indexed(255));
+        Table::new(rows, [Constraint::Length(14), Constraint::Length(16), Constraint::Length(16)]).header(header)
+    }
+}
+
+impl Renderable<EmptyRenderableArgs> for StatsScreen {
+    fn render(&mut self, frame: &mut Frame, _args: EmptyRenderableArgs) {
+        let mut key_bindings_block = KeyBindingsBlock::new(self.config_key_bindings.clone());
+        let key_bindings = vec![
+            key_bindings_block.cnf_kb_home(),
+            key_bindings_block.cnf_kb_help(),
+            KeyBindingsBlock::kb_cancel(),
+            key_bindings_block.cnf_kb_quit()
+        ];
+        let block = Block::bordered()
+            .title(Line::from(" Statistics ".bold()).centered())
+            .title_bottom(KeyBindingsBlock::key_bindings_as_line(&key_bindings).centered())
+            .padding(Padding::proportional(1))
+            .border_set(border::THICK);
+        let area = block.inner(frame.area());
+        frame.render_widget(block, frame.area());
+
+        let session_stats = match &self.session_stats {
+            Some(Ok(session_stats)) => session_stats,
+            Some(Err(e)) => {
+                frame.render_widget(Paragraph::new(e.to_string().red()).centered(), area);
+                return;
+            }
+            None => {
+                frame.render_widget(Paragraph::new("Loading...".dim()).centered(), area);
+                return;
+            }
+        };
+
+        let stats = &session_stats.stats;
+        let free_space = &session_stats.free_space;
+        let disk = match free_space.total_size {
+            Some(total_size) => format!(
+                "{} free of {}",
+                Util::print_bytes(free_space.size_bytes as f64),
+                Util::print_bytes(total_size as f64)
+            ),
+            None => format!("{} free", Util::print_bytes(free_space.size_bytes as f64))
+        };
+        let summary = vec![
+            Line::from(vec![
+                "Speed: ".bold(),
+                format!("\u{2193} {}/s  \u{2191} {}/s", Util::print_bytes(stats.download_speed as f64), Util::print_bytes(stats.upload_speed as f64)).into()
+            ]),
+            Line::from(vec![
+                "Torrents: ".bold(),
+                format!("{} ({} active, {} paused)", stats.torrent_count, stats.active_torrent_count, stats.paused_torrent_count).into()
+            ]),
+            Line::from(vec!["Disk: ".bold(), format!("{} in {}", disk, free_space.path).into()]),
+            Line::from(vec!["Overall ratio: ".bold(), Self::ratio(&stats.cumulative_stats).into()]),
+        ];
+        let [summary_area, totals_area] = Layout::vertical([Constraint::Length(5), Constraint::Fill(1)]).areas(area);
+        frame.render_widget(Paragraph::new(summary), summary_area);
+        frame.render_widget(Self::totals_table(session_stats), totals_area);
+    }
+}
+
+impl KeyEventHandler for StatsScreen {
+    /*
+       Returns false if we are done from this screen
+    */
+    fn handle_key_event(&mut self, key_event: KeyEvent, _event: Event) -> bool {
+        !(key_event.kind == KeyEventKind::Press && key_event.code == KeyCode::Esc)
+    }
+}
@@ -0,0 +1,303 @@
+use std::sync::Arc;
+use crossterm::event::{Event, KeyCode, KeyEvent, KeyEventKind};
+use ratatui::Frame;
+use ratatui::layout::{Constraint, Layout, Rect};
+use ratatui::prelude::{Color, Line, Modifier, Style, Stylize};
+use ratatui::widgets::{Block, Cell, Padding, Row, Table, TableState};
+use tui_input::Input;
+use tui_input::backend::crossterm::EventHandler;
+use crate::app::KeyEventHandler;
+use crate::dto::transmission_dto::{TransmissionTorrent, TransmissionTorrentTrackerStat};
+use crate::poller::Poller;
+use crate::screen::key_bindings_block::KeyBindingsBlock;
+use crate::screen::popup_screen::Notifier;
+use crate::service::transmission_service::TransmissionService;
+use crate::util::Util;
+
+#[derive(Clone, Copy, PartialEq)]
+enum InputMode {
+    None,
+    Add,
+    Replace,
+    // waiting for the removal of the selected tracker to be confirmed
+    Remove
+}
+
+/*
+    Trackers tab of the Info screen, the trackers grouped by tier with their announce / scrape status.
+    Trackers can be added, removed (once confirmed) or have their announce url replaced.
+*/
+pub struct TrackersTab {
+    transmission_service: Arc<TransmissionService>,
+    notifier: Notifier,
+    poller: Poller,
+    table_state: TableState,
+    // index in `trackers` (the tier rows of the table are not selectable)
+    selected: usize,
+    torrent: TransmissionTorrent,
+    // ordered by tier
+    trackers: Vec<TransmissionTorrentTrackerStat>,
+    input: Input,
+    input_mode: InputMode,
+    // tracker being replaced or removed, taken when asked for since the selection may point elsewhere
+    // once a poll reorders the trackers
+    target: Option<TransmissionTorrentTrackerStat>
+}
+
+impl TrackersTab {
+
+    pub fn new(transmission_service: Arc<TransmissionService>, notifier: Notifier, poller: Poller) -> Self {
+        Self {
+            transmission_service,
+            notifier,
+            poller,
+            table_state: TableState::default(),
+            selected: 0,
+            torrent: TransmissionTorrent::default(),
+            trackers: vec![],
+            input: Input::default(),
+            input_mode: InputMode::None,
+            target: None
+        }
+    }
+
+    // While typing an announce url (or confirming a removal) every key belongs to this tab
+    pub fn is_editing(&self) -> bool {
+        self.input_mode != InputMode::None
+    }
+
+    pub fn render(&mut self, frame: &mut Frame, area: Rect, torrent: &TransmissionTorrent) {
+        if torrent.id != self.torrent.id {
+            self.selected = 0;
+            self.input_mode = InputMode::None;
+        }
+        self.torrent = torrent.clone();
+        self.trackers = torrent.tracker_stats.clone();
+        self.trackers.sort_by_key(|tracker| (tracker.tier, tracker.id));
+        self.selected = self.selected.min(self.trackers.len().saturating_sub(1));
+
+        let key_bindings = match self.input_mode {
+            InputMode::Remove => vec![KeyBindingsBlock::kb_confirm(), KeyBindingsBlock::kb_cancel()],
+            _ => vec![
+                KeyBindingsBlock::kb_add_tracker(),
+                KeyBindingsBlock::kb_edit_tracker(),
+                KeyBindingsBlock::kb_remove_tracker(),
+                KeyBindingsBlock::kb_cancel()
+            ]
+        };
+        let block = Block::bordered()
+            .title(Line::from(format!(" {} trackers ", self.trackers.len()).bold()).centered())
+            .title_bottom(KeyBindingsBlock::key_bindings_as_line(&key_bindings).centered())
+            .padding(Padding::horizontal(1));
+        let input_height = if self.is_editing() { 1 } else { 0 };
+        let [input_area, table_area] = Layout::vertical([Constraint::Length(input_height), Constraint::Fill(1)])
+            .areas(block.inner(area));
+        frame.render_widget(block, area);
+        if self.is_editing() {
+            self.render_input(frame, input_area);
+        }
+        let (table, selected_row) = self.table();
+        self.table_state.select(selected_row);
+        frame.render_stateful_widget(table, table_area, &mut self.table_state);
+    }
+
+    fn render_input(&self, frame: &mut Frame, area: Rect) {
+        if self.input_mode == InputMode::Remove {
+            let announce = self.target.as_ref().map(|tracker| tracker.announce.clone()).unwrap_or_default();
+            frame.render_widget(Line::from(vec!["Remove ".red().bold(), announce.into(), "?".red().bold()]), area);
+            return;
+        }
+        let label = "Announce url: ";
+        let width = area.width.saturating_sub(label.len() as u16 + 1);
+        let scroll = self.input.visual_scroll(width as usize);
+        let value: String = self.input.value().chars().skip(scroll).collect();
+        frame.render_widget(Line::from(vec![label.bold(), value.into()]), area);
+        let x = self.input.visual_cursor().max(scroll) - scroll;
+        frame.set_cursor_position((area.x + label.len() as u16 + x as u16, area.y));
+    }
+
+    // Announce state, see `announceState` in https://github.com/transmission/transmission/blob/main/docs/rpc-spec.md
+    fn announce_state(tracker: &TransmissionTorrentTrackerStat) -> &'static str {
+        match tracker.announce_state {
+            1 => "Waiting",
+            2 => "Queued",
+            3 => "Announcing",
+            _ => "Inactive"
+        }
+    }
+
+    fn last_announce(tracker: &TransmissionTorrentTrackerStat, now: i64) -> String {
+        if tracker.has_announced {
+            format!("{} ago", Util::print_duration(now - tracker.last_announce_time))
+        } else {
+            "Never".to_string()
+        }
+    }
+
+    fn next_announce(tracker: &TransmissionTorrentTrackerStat, now: i64) -> String {
+        if tracker.next_announce_time > now {
+            format!("in {}", Util::print_duration(tracker.next_announce_time - now))
+        } else {
+            "-".to_string()
+        }
+    }
+
+    // The daemon reports -1 when the tracker was not scraped yet
+    fn count(count: i64) -> String {
+        if count < 0 { "-".to_string() } else { count.to_string() }
+    }
+
+    // Also returns the row of the selected tracker
+    fn table(&self) -> (Table<'static>, Option<usize>) {
+        let now = Util::now_epoch();
+        let mut rows = vec![];
+        let mut selected_row = None;
+        let mut tier = None;
+        self.trackers.iter().enumerate().for_each(|(i, tracker)| {
+            if tier != Some(tracker.tier) {
+                tier = Some(tracker.tier);
+                rows.push(Row::new(vec![Cell::from(format!("Tier {}", tracker.tier + 1).bold())]));
+            }
+            if i == self.selected {
+                selected_row = Some(rows.len());
+            }
+            let result = if tracker.has_announced && !tracker.last_announce_succeeded {
+                Cell::from(tracker.last_announce_result.clone().red())
+            } else {
+                Cell::from(tracker.last_announce_result.clone())
+            };
+            rows.push(Row::new(vec![
+                Cell::from(format!("  {}", tracker.announce)),
+                Cell::from(Self::announce_state(tracker)),
+                Cell::from(Self::last_announce(tracker, now)),
+                Cell::from(Self::next_announce(tracker, now)),
+                Cell::from(Self::count(tracker.seeder_count)),
+                Cell::from(Self::count(tracker.leecher_count)),
+                result,
+            ]));
+        });
+        let header = ["Announce", "State", "Last announce", "Next announce", "Seeders", "Leechers", "Result"]
+            .into_iter()
+            .map(Cell::from)
+            .collect::<Row>()
+            .height(1)
+            .bg(Color::Indexed(236)) // https://en.wikipedia.org/wiki/ANSI_escape_code#Colors
+            .fg(Color::Indexed(255));
+        let selected_row_style = Style::default()
+            .add_modifier(Modifier::REVERSED)
+            .fg(Color::Indexed(255)) // https://en.wikipedia.org/wiki/ANSI_escape_code#Colors
+            .bg(Color::Black);
+        let table = Table::new(
+            rows,
+            [
+                Constraint::Fill(2),
+                Constraint::Length(11),
+                Constraint::Length(14),
+                Constraint::Length(14),
+                Constraint::Length(8),
+                Constraint::Length(9),
+                Constraint::Fill(1),
+            ],
+        )
+        .header(header)
+        .row_highlight_style(selected_row_style);
+        (table, selected_row)
+    }
+
+    fn selected_tracker(&self) -> Option<&TransmissionTorrentTrackerStat> {
+        self.trackers.get(self.selected)
+    }
+
+    fn edit(&mut self, input_mode: InputMode) {
+        self.target = self.selected_tracker().cloned();
+        let announce = match (input_mode, &self.target) {
+            (InputMode::Replace, Some(tracker)) => tracker.announce.clone(),
+            (InputMode::Replace, None) => return,
+            _ => String::new()
+        };
+        self.input = Input::new(announce);
+        self.input_mode = input_mode;
+    }
+
+    fn apply_input(&mut self) {
+        let announce = self.input.value().trim().to_string();
+        if announce.is_empty() {
+            return;
+        }
+        let result = match (self.input_mode, self.target.take()) {
+            (InputMode::Add, _) => self.transmission_service.torrent_add_tracker(&self.torrent, &announce),
+            (InputMode::Replace, Some(tracker)) => {
+                self.transmission_service.torrent_replace_tracker(&self.torrent, tracker.id, &announce)
+            }
+            _ => return
+        };
+        let done = if self.input_mode == InputMode::Add { "Added" } else { "Replaced with" };
+        self.notifier.result(&result, format!("{} {}", done, announce));
+        self.poller.refresh();
+    }
+
+    fn confirm_remove(&mut self) {
+        self.target = self.selected_tracker().cloned();
+        if self.target.is_some() {
+            self.input_mode = InputMode::Remove;
+        }
+    }
+
+    fn remove(&mut self) {
+        let Some(tracker) = self.target.take() else {
+            return;
+        };
+        let result = self.transmission_service.torrent_remove_tracker(&self.torrent, tracker.id);
+        self.notifier.result(&result, format!("Removed {}", tracker.announce));
+        self.poller.refresh();
+    }
+}
+
+impl KeyEventHandler for TrackersTab {
+    /*
+       Returns false if we are done from this screen
+    */
+    fn handle_key_event(&mut self, key_event: KeyEvent, event: Event) -> bool {
+        if key_event.kind != KeyEventKind::Press {
+            return true;
+        }
+        if self.input_mode == InputMode::Remove {
+            match key_event.code {
+                KeyCode::Enter => {
+                    self.remove();
+                    self.input_mode = InputMode::None;
+                }
+                KeyCode::Esc => self.input_mode = InputMode::None,
+                _ => {}
+            }
+            return true;
+        }
+        if self.is_editing() {
+            match key_event.code {
+                KeyCode::Enter => {
+                    self.apply_input();
+                    self.input_mode = InputMode::None;
+                }
+                KeyCode::Esc => self.input_mode = InputMode::None,
+                // let input handle it
+                _ => {
+                    self.input.handle_event(&event);
+                }
+            }
+            return true;
+        }
+        match key_event.code {
+            KeyCode::Char('j') | KeyCode::Down => {
+                self.selected = (self.selected + 1).min(self.trackers.len().saturating_sub(1));
+            }
+            KeyCode::Char('k') | KeyCode::Up => self.selected = self.selected.saturating_sub(1),
+            KeyCode::Char('a') => self.edit(InputMode::Add),
+            KeyCode::Char('e') => self.edit(InputMode::Replace),
+            KeyCode::Char('d') => self.confirm_remove(),
+            // leave
+            KeyCode::Esc => return false,
+            _ => {}
+        }
+        true
+    }
+}
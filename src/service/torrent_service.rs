@@ -1,6 +1,6 @@
 use crate::client::http_client::HttpClient;
 use crate::dto::torrent_dto::{TorrentsCsvTorrent, TorrentsCsvResponse, PirateBayTorrentFile, PirateBayInfoTorrent, PirateBayListTorrent};
-use std::io::{Error, ErrorKind};
+use crate::error::{RustorError, RustorResult};
 
 pub struct TorrentService {
     http_client: HttpClient
@@ -33,8 +33,10 @@ impl TorrentService {
               ...
           ]
      */
-    pub fn search_pirate_bay(&self, search_term: &str) -> Result<Vec<PirateBayListTorrent>, Error> {
-        self.http_client.request(format!("https://apibay.org/q.php?q={}", search_term))
+    pub fn search_pirate_bay(&self, search_term: &str) -> RustorResult<Vec<PirateBayListTorrent>> {
+        self.http_client
+            .request(format!("https://apibay.org/q.php?q={}", search_term))
+            .map_err(|e| Self::provider_error("PirateBay", e))
     }
 
     /*
@@ -59,8 +61,10 @@ impl TorrentService {
               "info_hash": "0C23E50E075C634CFD5CD8A09A82F6EEE18D72A8"
             }
      */
-    pub fn torrent_info_pirate_bay(&self, torrent_id: i64) -> Result<PirateBayInfoTorrent, Error> {
-        self.http_client.request(format!("https://apibay.org/t.php?id={}", torrent_id))
+    pub fn torrent_info_pirate_bay(&self, torrent_id: i64) -> RustorResult<PirateBayInfoTorrent> {
+        self.http_client
+            .request(format!("https://apibay.org/t.php?id={}", torrent_id))
+            .map_err(|e| Self::provider_error("PirateBay", e))
     }
 
     /*
@@ -78,8 +82,10 @@ impl TorrentService {
               ...
             ]
      */
-    pub fn torrent_files_pirate_bay(&self, torrent_id: i64) -> Result<Vec<PirateBayTorrentFile>, Error> {
-        self.http_client.request(format!("https://apibay.org/f.php?id={}", torrent_id))
+    pub fn torrent_files_pirate_bay(&self, torrent_id: i64) -> RustorResult<Vec<PirateBayTorrentFile>> {
+        self.http_client
+            .request(format!("https://apibay.org/f.php?id={}", torrent_id))
+            .map_err(|e| Self::provider_error("PirateBay", e))
     }
 
     /*
@@ -102,22 +108,27 @@ impl TorrentService {
             ],
             "next": <next-torrent-id>
      */
-    pub fn search_torrents_csv(&self, search_term: &str) -> Result<Vec<TorrentsCsvTorrent>, Error> {
+    pub fn search_torrents_csv(&self, search_term: &str) -> RustorResult<Vec<TorrentsCsvTorrent>> {
         if search_term.is_empty() {
-            Err(Error::new(ErrorKind::InvalidData, "Empty search term!"))
+            Err(RustorError::SearchProvider("TorrentsCsv: Empty search term!".to_string()))
         } else {
             let size_result = search_term.chars().size_hint();
             match size_result.1 {
                 Some(size) =>
                     if size < 3 {
-                        Err(Error::new(ErrorKind::InvalidData, "Search term too short!"))
+                        Err(RustorError::SearchProvider("TorrentsCsv: Search term too short!".to_string()))
                     } else {
-                        let result: TorrentsCsvResponse = self.http_client.request(format!("https://torrents-csv.com/service/search?size=20&q={}", search_term))?;
+                        let result: TorrentsCsvResponse = self.http_client
+                            .request(format!("https://torrents-csv.com/service/search?size=20&q={}", search_term))
+                            .map_err(|e| Self::provider_error("TorrentsCsv", e))?;
                         Ok(result.torrents)
                     },
-                None => Err(Error::new(ErrorKind::InvalidData, "Can't calculate search term length!"))
+                None => Err(RustorError::SearchProvider("TorrentsCsv: Can't calculate search term length!".to_string()))
             }
         }
     }
-}
 
+    fn provider_error(provider: &str, error: RustorError) -> RustorError {
+        RustorError::SearchProvider(format!("{}: {}", provider, error))
+    }
+}
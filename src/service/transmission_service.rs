@@ -1,73 +1,414 @@
-use std::process::Command;
+use std::fs;
+use std::sync::RwLock;
+use base64::Engine;
+use base64::engine::general_purpose::STANDARD;
+use serde_json::{json, Map, Value};
 use crate::client::cli_client::CliClient;
-use crate::dto::transmission_dto::{TransmissionResponse, TransmissionTorrent};
+use crate::client::rpc_client::RpcClient;
+use std::collections::BTreeMap;
+use serde::de::DeserializeOwned;
+use crate::dto::transmission_dto::{
+    TransmissionFreeSpace, TransmissionGroup, TransmissionGroups, TransmissionRequest, TransmissionResponse, TransmissionSession, TransmissionSessionResponse,
+    TransmissionSessionStats, TransmissionTorrent, TransmissionTorrentPeer, TransmissionTorrentTrackerStat, TransmissionTorrentValues, PRIORITY_HIGH, PRIORITY_LOW
+};
+use crate::error::{RustorError, RustorResult};
 
-pub struct TransmissionService {}
+// Fields needed by the torrent list (Home screen)
+const LIST_FIELDS: &[&str] = &[
+    "id", "name", "status", "error", "errorString", "eta", "isFinished", "leftUntilDone", "sizeWhenDone",
+    "rateDownload", "rateUpload", "addedDate", "peers", "peersGettingFromUs", "peersSendingToUs", "uploadRatio", "labels",
+    "trackers", "downloadDir", "recheckProgress", "corruptEver", "group"
+];
+
+// Fields needed by the torrent details (Info screen)
+const INFO_FIELDS: &[&str] = &[
+    "activityDate", "addedDate", "bandwidthPriority", "comment", "corruptEver", "creator", "dateCreated",
+    "desiredAvailable", "doneDate", "downloadDir", "downloadLimit", "downloadLimited", "downloadedEver", "error",
+    "errorString", "eta", "group", "hashString", "haveUnchecked", "haveValid", "honorsSessionLimits", "id",
+    "isFinished", "isPrivate", "labels", "leftUntilDone", "magnetLink", "name", "peer-limit", "peersConnected",
+    "peersGettingFromUs", "peersSendingToUs", "pieceCount", "pieceSize", "rateDownload", "rateUpload",
+    "recheckProgress", "secondsDownloading", "secondsSeeding", "seedIdleLimit", "seedIdleMode", "seedRatioLimit",
+    "seedRatioMode", "sequential_download", "sizeWhenDone", "source", "startDate", "status", "totalSize",
+    "uploadLimit", "uploadLimited", "uploadRatio", "uploadedEver", "webseeds", "webseedsSendingToUs", "files",
+    "peers", "trackerStats", "priorities", "wanted", "pieces"
+];
+
+// First rpc-version (Transmission 4.0) with `trackerList`, which replaces `trackerAdd` / `trackerRemove` / `trackerReplace`
+const TRACKER_LIST_RPC_VERSION: i64 = 17;
+
+pub struct TransmissionService {
+    rpc_client: RwLock<RpcClient>
+}
 
 impl TransmissionService {
 
+    pub fn new(rpc_client: RpcClient) -> Self {
+        Self { rpc_client: RwLock::new(rpc_client) }
+    }
+
+    // Route every following call to another daemon
+    pub fn connect(&self, rpc_client: RpcClient) {
+        *self.rpc_client.write().unwrap() = rpc_client;
+    }
+
+    // Whether the daemon currently talked to shares our filesystem
+    pub fn is_local(&self) -> bool {
+        self.rpc_client.read().unwrap().is_local()
+    }
+
     /*
        @tor: Torrent local filename or magnet-link
+       Local files are sent as base64 `metainfo` so the daemon does not need access to our filesystem.
+    */
+    pub fn torrent_add(&self, tor: String) -> RustorResult<TransmissionResponse> {
+        let arguments = match fs::read(&tor) {
+            Ok(content) => json!({ "metainfo": STANDARD.encode(content) }),
+            Err(_) => json!({ "filename": tor })
+        };
+        self.call("torrent-add", arguments)
+    }
+
+    // With `delete_local_data` the downloaded files are deleted as well
+    pub fn torrent_remove(&self, tor_ids: &[i64], delete_local_data: bool) -> RustorResult<TransmissionResponse> {
+        self.call("torrent-remove", json!({ "ids": tor_ids, "delete-local-data": delete_local_data }))
+    }
+
+    pub fn torrent_list(&self) -> RustorResult<TransmissionResponse> {
+        self.call("torrent-get", json!({ "fields": LIST_FIELDS }))
+    }
+
+    // Same fields as `torrent_list` but only for the torrents changed lately, plus the ids of the removed ones
+    pub fn torrent_list_recently_active(&self) -> RustorResult<TransmissionResponse> {
+        self.call("torrent-get", json!({ "ids": "recently-active", "fields": LIST_FIELDS }))
+    }
+
+    pub fn torrent_start(&self, tor_ids: &[i64]) -> RustorResult<TransmissionResponse> {
+        self.call("torrent-start", json!({ "ids": tor_ids }))
+    }
+
+    pub fn torrent_stop(&self, tor_ids: &[i64]) -> RustorResult<TransmissionResponse> {
+        self.call("torrent-stop", json!({ "ids": tor_ids }))
+    }
+
+    pub fn torrent_reannounce(&self, tor_ids: &[i64]) -> RustorResult<TransmissionResponse> {
+        self.call("torrent-reannounce", json!({ "ids": tor_ids }))
+    }
+
+    pub fn torrent_verify(&self, tor_ids: &[i64]) -> RustorResult<TransmissionResponse> {
+        self.call("torrent-verify", json!({ "ids": tor_ids }))
+    }
+
+    // Replaces the labels of every given torrent
+    pub fn torrent_set_labels(&self, tor_ids: &[i64], labels: &[String]) -> RustorResult<TransmissionResponse> {
+        self.call("torrent-set", json!({ "ids": tor_ids, "labels": labels }))
+    }
+
+    // An empty `group` takes the torrents out of their bandwidth group
+    pub fn torrent_set_group(&self, tor_ids: &[i64], group: &str) -> RustorResult<TransmissionResponse> {
+        self.call("torrent-set", json!({ "ids": tor_ids, "group": group }))
+    }
+
+    pub fn torrent_info(&self, tor_id: String) -> RustorResult<TransmissionResponse> {
+        self.torrent_get(&tor_id, INFO_FIELDS)
+    }
+
+    pub fn torrent_set_files_wanted(&self, tor_id: i64, files: &[usize], wanted: bool) -> RustorResult<TransmissionResponse> {
+        let key = if wanted { "files-wanted" } else { "files-unwanted" };
+        self.call("torrent-set", json!({ "ids": [tor_id], key: files }))
+    }
+
+    // @priority: one of `PRIORITY_LOW`, `PRIORITY_NORMAL`, `PRIORITY_HIGH`
+    pub fn torrent_set_files_priority(&self, tor_id: i64, files: &[usize], priority: i64) -> RustorResult<TransmissionResponse> {
+        let key = match priority {
+            PRIORITY_LOW => "priority-low",
+            PRIORITY_HIGH => "priority-high",
+            _ => "priority-normal"
+        };
+        self.call("torrent-set", json!({ "ids": [tor_id], key: files }))
+    }
+
+    // Raw values of the given fields for every given torrent, keyed by their rpc name
+    pub fn torrent_get_values(&self, tor_ids: &[i64], fields: &[&str]) -> RustorResult<Vec<Map<String, Value>>> {
+        self.call_as::<TransmissionTorrentValues>("torrent-get", json!({ "ids": tor_ids, "fields": fields }))
+            .map(|values| values.torrents)
+    }
+
+    // Only the given fields change, keyed by their rpc name, on every given torrent
+    pub fn torrent_set_values(&self, tor_ids: &[i64], values: Map<String, Value>) -> RustorResult<TransmissionResponse> {
+        let mut arguments = values;
+        arguments.insert("ids".to_string(), json!(tor_ids));
+        self.call("torrent-set", Value::Object(arguments))
+    }
+
+    /*
+       @path: the torrent name or the path of a file / directory inside it (e.g. `name/dir/file`)
+       @name: new last component of `path`, see `Util::validate_file_name`
+    */
+    pub fn torrent_rename_path(&self, tor_id: i64, path: &str, name: &str) -> RustorResult<TransmissionResponse> {
+        self.call("torrent-rename-path", json!({ "ids": [tor_id], "path": path, "name": name }))
+    }
+
+    // Only the peers, lighter than `torrent_info` for the Peers tab to fetch between polls
+    pub fn torrent_peers(&self, tor_id: i64) -> RustorResult<Vec<TransmissionTorrentPeer>> {
+        self.torrent_get(&tor_id.to_string(), &["id", "peers"])
+            .map(|response| response.arguments.torrents.into_iter().next().map(|tor| tor.peers).unwrap_or_default())
+    }
+
+    // The announce url becomes a tier of its own
+    pub fn torrent_add_tracker(&self, tor: &TransmissionTorrent, announce: &str) -> RustorResult<TransmissionResponse> {
+        if self.supports_tracker_list() {
+            self.torrent_set_tracker_list(tor, |tracker| Some(tracker.announce.clone()), Some(announce))
+        } else {
+            self.call("torrent-set", json!({ "ids": [tor.id], "trackerAdd": [announce] }))
+        }
+    }
+
+    pub fn torrent_remove_tracker(&self, tor: &TransmissionTorrent, tracker_id: i64) -> RustorResult<TransmissionResponse> {
+        if self.supports_tracker_list() {
+            self.torrent_set_tracker_list(tor, |tracker| (tracker.id != tracker_id).then(|| tracker.announce.clone()), None)
+        } else {
+            self.call("torrent-set", json!({ "ids": [tor.id], "trackerRemove": [tracker_id] }))
+        }
+    }
+
+    pub fn torrent_replace_tracker(&self, tor: &TransmissionTorrent, tracker_id: i64, announce: &str) -> RustorResult<TransmissionResponse> {
+        if self.supports_tracker_list() {
+            let announce_of = |tracker: &TransmissionTorrentTrackerStat| {
+                Some(if tracker.id == tracker_id { announce.to_string() } else { tracker.announce.clone() })
+            };
+            self.torrent_set_tracker_list(tor, announce_of, None)
+        } else {
+            self.call("torrent-set", json!({ "ids": [tor.id], "trackerReplace": [tracker_id, announce] }))
+        }
+    }
+
+    /*
+       @location: directory of the daemon's host
+       @move_data: move the data there, or just look for it there (e.g. after it was moved by hand)
     */
-    pub fn torrent_add(tor: String) -> TransmissionResponse {
-        Self::json_to_response(CliClient::run_command(format!("transmission-remote -j -a {}", tor)))
+    pub fn torrent_set_location(&self, tor_ids: &[i64], location: &str, move_data: bool) -> RustorResult<TransmissionResponse> {
+        self.call("torrent-set-location", json!({ "ids": tor_ids, "location": location, "move": move_data }))
     }
 
-    pub fn torrent_remove(tor_id: String) -> TransmissionResponse {
-        Self::json_to_response(CliClient::run_command(format!("transmission-remote -j -t {} -r", tor_id)))
+    pub fn torrent_location(&self, tor: &TransmissionTorrent) -> RustorResult<()> {
+        CliClient::run_command(format!("xdg-open {}", tor.download_dir)).map(|_| ())
     }
 
-    pub fn torrent_list() -> TransmissionResponse {
-        Self::json_to_response(CliClient::run_command("transmission-remote -j -l".parse().unwrap()))
+    pub fn session_get(&self, fields: &[&str]) -> RustorResult<TransmissionSession> {
+        self.call_as("session-get", json!({ "fields": fields }))
     }
 
-    pub fn torrent_start(tor_id: String) -> TransmissionResponse {
-        Self::json_to_response(CliClient::run_command(format!("transmission-remote -j -t {} -s", tor_id)))
+    // Raw values of the given settings, keyed by their rpc name
+    pub fn session_get_values(&self, fields: &[&str]) -> RustorResult<Map<String, Value>> {
+        self.call_as("session-get", json!({ "fields": fields }))
     }
 
-    pub fn torrent_stop(tor_id: String) -> TransmissionResponse {
-        Self::json_to_response(CliClient::run_command(format!("transmission-remote -j -t {} -S", tor_id)))
+    // Only the given settings change, keyed by their rpc name
+    pub fn session_set(&self, values: Map<String, Value>) -> RustorResult<()> {
+        self.call_as::<Map<String, Value>>("session-set", Value::Object(values)).map(|_| ())
     }
 
-    pub fn torrent_reannounce(tor_id: String) -> TransmissionResponse {
-        Self::json_to_response(CliClient::run_command(format!("transmission-remote -j -t {} --reannounce", tor_id)))
+    // Every bandwidth group, daemons older than rpc-version 17 answer with an error
+    pub fn group_get(&self) -> RustorResult<Vec<TransmissionGroup>> {
+        self.call_as::<TransmissionGroups>("group-get", json!({})).map(|groups| groups.group)
     }
 
-    pub fn torrent_info(tor_id: String) -> TransmissionResponse {
-        Self::json_to_response(CliClient::run_command(format!("transmission-remote -j -t {} -i", tor_id)))
+    // Only the given values change, keyed by their rpc name, the group is created when missing
+    pub fn group_set(&self, name: &str, values: Map<String, Value>) -> RustorResult<()> {
+        let mut arguments = values;
+        arguments.insert("name".to_string(), json!(name));
+        self.call_as::<Map<String, Value>>("group-set", Value::Object(arguments)).map(|_| ())
     }
 
-    pub fn torrent_files(tor_id: String) -> TransmissionResponse {
-        Self::json_to_response(CliClient::run_command(format!("transmission-remote -j -t {} -f", tor_id)))
+    pub fn session_stats(&self) -> RustorResult<TransmissionSessionStats> {
+        self.call_as("session-stats", json!({}))
     }
 
-    pub fn torrent_peers(tor_id: String) -> TransmissionResponse {
-        Self::json_to_response(CliClient::run_command(format!("transmission-remote -j -t {} -ip", tor_id)))
+    // Free space in a directory of the daemon's host
+    pub fn free_space(&self, path: &str) -> RustorResult<TransmissionFreeSpace> {
+        self.call_as("free-space", json!({ "path": path }))
     }
 
-    pub fn torrent_trackers(tor_id: String) -> TransmissionResponse {
-        Self::json_to_response(CliClient::run_command(format!("transmission-remote -j -t {} -it", tor_id)))
+    // Liveness probe, the daemon is considered active when it answers a minimal `session-get`
+    pub fn probe(&self) -> RustorResult<()> {
+        self.session_get(&["version"]).map(|_| ())
     }
 
-    pub fn torrent_location(tor: &TransmissionTorrent) {
-        CliClient::run_command(format!("xdg-open {}", tor.download_dir));
+    fn supports_tracker_list(&self) -> bool {
+        self.session_get(&["rpc-version"])
+            .map(|session| session.rpc_version >= TRACKER_LIST_RPC_VERSION)
+            .unwrap_or(false)
     }
 
-    pub fn transmission_daemon_is_active() -> bool {
-        let result = &CliClient::run_command("systemctl is-active transmission-daemon.service".to_string())[0];
+    fn torrent_set_tracker_list(
+        &self,
+        tor: &TransmissionTorrent,
+        announce_of: impl Fn(&TransmissionTorrentTrackerStat) -> Option<String>,
+        added: Option<&str>
+    ) -> RustorResult<TransmissionResponse> {
+        self.call("torrent-set", json!({ "ids": [tor.id], "trackerList": Self::tracker_list(tor, announce_of, added) }))
+    }
 
-        if result.is_empty() {
-            println!(
-                "Could not retrieve the transmission-daemon's status, maybe is not running or is not even installed"
-            );
-            false
+    /*
+       `trackerList` holds the announce urls one per line, tiers being separated by a blank line.
+       @announce_of: announce url to keep for every current tracker, `None` drops the tracker
+       @added: announce url appended as the last tier
+    */
+    fn tracker_list(
+        tor: &TransmissionTorrent,
+        announce_of: impl Fn(&TransmissionTorrentTrackerStat) -> Option<String>,
+        added: Option<&str>
+    ) -> String {
+        let mut tiers: BTreeMap<i64, Vec<String>> = BTreeMap::new();
+        tor.tracker_stats.iter().for_each(|tracker| {
+            if let Some(announce) = announce_of(tracker) {
+                tiers.entry(tracker.tier).or_default().push(announce);
+            }
+        });
+        let mut tiers: Vec<String> = tiers.into_values().map(|announces| announces.join("\n")).collect();
+        tiers.extend(added.map(str::to_string));
+        tiers.join("\n\n")
+    }
+
+    fn torrent_get(&self, tor_id: &str, fields: &[&str]) -> RustorResult<TransmissionResponse> {
+        let mut arguments = Self::ids(tor_id);
+        arguments["fields"] = json!(fields);
+        self.call("torrent-get", arguments)
+    }
+
+    // The daemon answers every request with a `result` which is "success" or an error message
+    fn call(&self, method: &str, arguments: Value) -> RustorResult<TransmissionResponse> {
+        let response: TransmissionResponse = self.rpc_client
+            .read()
+            .unwrap()
+            .request(&TransmissionRequest::new(method, arguments))?;
+        if response.result == "success" {
+            Ok(response)
+        } else {
+            Err(RustorError::Rpc(response.result))
+        }
+    }
+
+    fn call_as<T: DeserializeOwned + Default>(&self, method: &str, arguments: Value) -> RustorResult<T> {
+        let response: TransmissionSessionResponse<T> = self.rpc_client
+            .read()
+            .unwrap()
+            .request(&TransmissionRequest::new(method, arguments))?;
+        if response.result == "success" {
+            Ok(response.arguments)
         } else {
-            !result.contains("inactive") && result.contains("active")
+            Err(RustorError::Rpc(response.result))
+        }
+    }
+
+    fn ids(tor_id: &str) -> Value {
+        match tor_id.parse::<i64>() {
+            Ok(id) => json!({ "ids": [id] }),
+            Err(_) => json!({ "ids": [tor_id] })
         }
     }
+}
+
+#[cfg(test)]
+mod tests {
+    use tokio::runtime::Builder;
+    use crate::client::stub_daemon::serve;
+    use super::*;
+
+    const SUCCESS: &str = r#"{"arguments":{},"result":"success"}"#;
+    // `session-get` of Transmission 4.0 and 3.0
+    const SESSION_17: &str = r#"{"arguments":{"rpc-version":17},"result":"success"}"#;
+    const SESSION_16: &str = r#"{"arguments":{"rpc-version":16},"result":"success"}"#;
+
+    fn tracker(id: i64, tier: i64, announce: &str) -> TransmissionTorrentTrackerStat {
+        TransmissionTorrentTrackerStat { id, tier, announce: announce.to_string(), ..Default::default() }
+    }
+
+    // Two trackers in the first tier and one in the second, listed out of order
+    fn torrent() -> TransmissionTorrent {
+        TransmissionTorrent {
+            id: 7,
+            tracker_stats: vec![tracker(2, 1, "http://c/announce"), tracker(0, 0, "http://a/announce"), tracker(1, 0, "http://b/announce")],
+            ..Default::default()
+        }
+    }
+
+    fn service(url: String) -> TransmissionService {
+        let runtime = Builder::new_current_thread().enable_all().build().unwrap();
+        TransmissionService::new(RpcClient::new(runtime, url, None))
+    }
+
+    #[test]
+    fn tracker_list_keeps_the_tiers() {
+        let tracker_list = TransmissionService::tracker_list(&torrent(), |tracker| Some(tracker.announce.clone()), None);
+        assert_eq!(tracker_list, "http://a/announce\nhttp://b/announce\n\nhttp://c/announce");
+    }
+
+    #[test]
+    fn tracker_list_appends_an_added_tracker_as_a_tier() {
+        let tracker_list = TransmissionService::tracker_list(&torrent(), |tracker| Some(tracker.announce.clone()), Some("http://d/announce"));
+        assert_eq!(tracker_list, "http://a/announce\nhttp://b/announce\n\nhttp://c/announce\n\nhttp://d/announce");
+    }
+
+    #[test]
+    fn tracker_list_drops_removed_trackers_and_empty_tiers() {
+        let tracker_list = TransmissionService::tracker_list(&torrent(), |tracker| (tracker.id != 2).then(|| tracker.announce.clone()), None);
+        assert_eq!(tracker_list, "http://a/announce\nhttp://b/announce");
+    }
+
+    #[test]
+    fn tracker_list_of_a_torrent_without_trackers() {
+        let tor = TransmissionTorrent::default();
+        assert_eq!(TransmissionService::tracker_list(&tor, |tracker| Some(tracker.announce.clone()), None), "");
+        assert_eq!(TransmissionService::tracker_list(&tor, |tracker| Some(tracker.announce.clone()), Some("http://d")), "http://d");
+    }
+
+    #[test]
+    fn sends_a_tracker_list_from_rpc_version_17() {
+        let (url, server) = serve(vec![(200, vec![], SESSION_17), (200, vec![], SUCCESS)]);
+        service(url).torrent_replace_tracker(&torrent(), 1, "http://e/announce").unwrap();
+        let requests = server.join().unwrap();
+        assert_eq!(requests[0].json()["method"], "session-get");
+        let arguments = &requests[1].json()["arguments"];
+        assert_eq!(arguments["ids"], json!([7]));
+        assert_eq!(arguments["trackerList"], "http://a/announce\nhttp://e/announce\n\nhttp://c/announce");
+        assert!(arguments.get("trackerReplace").is_none());
+    }
+
+    #[test]
+    fn sends_tracker_add_remove_replace_before_rpc_version_17() {
+        let (url, server) = serve(vec![
+            (200, vec![], SESSION_16), (200, vec![], SUCCESS),
+            (200, vec![], SESSION_16), (200, vec![], SUCCESS),
+            (200, vec![], SESSION_16), (200, vec![], SUCCESS),
+        ]);
+        let service = service(url);
+        service.torrent_add_tracker(&torrent(), "http://d/announce").unwrap();
+        service.torrent_remove_tracker(&torrent(), 2).unwrap();
+        service.torrent_replace_tracker(&torrent(), 1, "http://e/announce").unwrap();
+        let requests = server.join().unwrap();
+        assert_eq!(requests[1].json()["arguments"]["trackerAdd"], json!(["http://d/announce"]));
+        assert_eq!(requests[3].json()["arguments"]["trackerRemove"], json!([2]));
+        assert_eq!(requests[5].json()["arguments"]["trackerReplace"], json!([1, "http://e/announce"]));
+        assert!(requests.iter().all(|request| request.json()["arguments"].get("trackerList").is_none()));
+    }
+
+    #[test]
+    fn torrent_peers_asks_for_the_peers_only() {
+        let body = r#"{"arguments":{"torrents":[{"id":7,"peers":[{"address":"10.0.0.2","flagStr":"DE"}]}]},"result":"success"}"#;
+        let (url, server) = serve(vec![(200, vec![], body)]);
+        let peers = service(url).torrent_peers(7).unwrap();
+        assert_eq!(peers.len(), 1);
+        assert_eq!((peers[0].address.as_str(), peers[0].flag_str.as_str()), ("10.0.0.2", "DE"));
+        let arguments = &server.join().unwrap()[0].json()["arguments"];
+        assert_eq!(arguments["ids"], json!([7]));
+        assert_eq!(arguments["fields"], json!(["id", "peers"]));
+    }
 
-    fn json_to_response(source: Vec<String>) -> TransmissionResponse {
-        let str = source.iter().map(|x| x.to_string()).collect::<String>();
-        serde_json::from_str(str.as_str()).unwrap()
+    #[test]
+    fn falls_back_to_tracker_add_when_the_rpc_version_is_unknown() {
+        let (url, server) = serve(vec![(500, vec![], ""), (200, vec![], SUCCESS)]);
+        service(url).torrent_add_tracker(&torrent(), "http://d/announce").unwrap();
+        assert_eq!(server.join().unwrap()[1].json()["arguments"]["trackerAdd"], json!(["http://d/announce"]));
     }
 }
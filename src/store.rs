@@ -0,0 +1,258 @@
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::sync::{Arc, RwLock};
+use std::time::{Duration, Instant};
+use crate::dto::transmission_dto::TransmissionTorrent;
+
+// A verification not seen running by then is taken as done (e.g. finished between two polls)
+const VERIFY_GRACE: Duration = Duration::from_secs(10);
+
+// A `torrent-set-location` the daemon is still working on
+#[derive(Clone)]
+pub struct Relocation {
+    pub location: String,
+    // false when the torrent is only pointed at data which is already there
+    pub move_data: bool,
+    pub started_on: Instant
+}
+
+// A `torrent-verify` the daemon is still working on
+struct Verification {
+    // of the torrent when asked for, to tell what the check found missing
+    left_until_done: i64,
+    is_seen_checking: bool,
+    started_on: Instant
+}
+
+// Outcome of a finished `torrent-verify`
+pub struct Verified {
+    pub torrent: TransmissionTorrent,
+    // bytes which were thought to be there but are not
+    pub missing: i64
+}
+
+/*
+    Torrents known to the daemon keyed by id, shared by the `Poller` (which keeps it up to date) and
+    the screens (which only read it). It is filled once with the whole list and from then on only
+    with the torrents the daemon reports as recently active or removed.
+    It also remembers the relocations asked for, until the daemon reports the torrent in its new location (or in error),
+    and the verifications asked for, until someone takes their outcome.
+*/
+#[derive(Clone, Default)]
+pub struct TorrentStore {
+    inner: Arc<RwLock<Inner>>
+}
+
+#[derive(Default)]
+struct Inner {
+    torrents: BTreeMap<i64, TransmissionTorrent>,
+    // false until the first full list arrives (and again after `clear`)
+    is_populated: bool,
+    // bumped by every change to `torrents`, see `TorrentStore::generation`
+    generation: u64,
+    relocations: HashMap<i64, Relocation>,
+    verifications: HashMap<i64, Verification>
+}
+
+impl Inner {
+
+    // The rpc does not tell how far a move is, only where the torrent is once done
+    fn finish_relocations(&mut self) {
+        let torrents = &self.torrents;
+        self.relocations.retain(|id, relocation| match torrents.get(id) {
+            Some(torrent) => torrent.error == 0
+                && torrent.download_dir.trim_end_matches('/') != relocation.location.trim_end_matches('/'),
+            None => false
+        });
+    }
+
+    // A check may be over before a poll sees it running, hence `VERIFY_GRACE`
+    fn track_verifications(&mut self) {
+        let torrents = &self.torrents;
+        self.verifications.retain(|id, _| torrents.contains_key(id));
+        self.verifications.iter_mut().for_each(|(id, verification)| {
+            verification.is_seen_checking |= torrents.get(id).is_some_and(|torrent| torrent.is_checking());
+        });
+    }
+}
+
+impl TorrentStore {
+
+    pub fn is_populated(&self) -> bool {
+        self.inner.read().unwrap().is_populated
+    }
+
+    // Forget everything (e.g. after connecting to another daemon), the next poll fetches the whole list
+    pub fn clear(&self) {
+        let mut inner = self.inner.write().unwrap();
+        *inner = Inner { generation: inner.generation + 1, ..Inner::default() };
+    }
+
+    // Tells readers which derive something from the torrents (e.g. the rows of Home) whether that is still current
+    pub fn generation(&self) -> u64 {
+        self.inner.read().unwrap().generation
+    }
+
+    pub fn replace(&self, torrents: Vec<TransmissionTorrent>) {
+        let mut inner = self.inner.write().unwrap();
+        inner.generation += 1;
+        inner.torrents = torrents.into_iter().map(|torrent| (torrent.id, torrent)).collect();
+        inner.is_populated = true;
+        inner.finish_relocations();
+        inner.track_verifications();
+    }
+
+    pub fn apply(&self, changed: Vec<TransmissionTorrent>, removed: &[i64]) {
+        let mut inner = self.inner.write().unwrap();
+        inner.generation += 1;
+        removed.iter().for_each(|id| { inner.torrents.remove(id); });
+        inner.torrents.extend(changed.into_iter().map(|torrent| (torrent.id, torrent)));
+        inner.finish_relocations();
+        inner.track_verifications();
+    }
+
+    // Track the given torrents as being relocated, see `TransmissionService::torrent_set_location`
+    pub fn relocate(&self, ids: &[i64], location: &str, move_data: bool) {
+        let relocation = Relocation { location: location.to_string(), move_data, started_on: Instant::now() };
+        self.inner.write().unwrap().relocations.extend(ids.iter().map(|id| (*id, relocation.clone())));
+    }
+
+    // Show a successful `torrent-rename-path` before the next poll does
+    pub fn rename_path(&self, id: i64, path: &str, name: &str) {
+        let mut inner = self.inner.write().unwrap();
+        inner.generation += 1;
+        if let Some(torrent) = inner.torrents.get_mut(&id) {
+            torrent.rename_path(path, name);
+        }
+    }
+
+    // Track the given torrents as being verified, see `TransmissionService::torrent_verify`
+    pub fn verify(&self, ids: &[i64]) {
+        let mut inner = self.inner.write().unwrap();
+        let verifications: Vec<(i64, Verification)> = ids
+            .iter()
+            .filter_map(|id| inner.torrents.get(id))
+            .map(|torrent| (torrent.id, Verification {
+                left_until_done: torrent.left_until_done,
+                is_seen_checking: false,
+                started_on: Instant::now()
+            }))
+            .collect();
+        inner.verifications.extend(verifications);
+    }
+
+    // Outcome of every verification which is over, each one is only returned once
+    pub fn take_verified(&self) -> Vec<Verified> {
+        let mut inner = self.inner.write().unwrap();
+        let done: Vec<i64> = inner.verifications
+            .iter()
+            .filter(|(id, verification)| {
+                let is_checking = inner.torrents.get(id).is_some_and(|torrent| torrent.is_checking());
+                !is_checking && (verification.is_seen_checking || verification.started_on.elapsed() > VERIFY_GRACE)
+            })
+            .map(|(id, _)| *id)
+            .collect();
+        done.into_iter()
+            .filter_map(|id| {
+                let verification = inner.verifications.remove(&id)?;
+                let torrent = inner.torrents.get(&id)?.clone();
+                let missing = (torrent.left_until_done - verification.left_until_done).max(0);
+                Some(Verified { torrent, missing })
+            })
+            .collect()
+    }
+
+    pub fn relocation(&self, id: i64) -> Option<Relocation> {
+        self.inner.read().unwrap().relocations.get(&id).cloned()
+    }
+
+    pub fn get(&self, id: i64) -> Option<TransmissionTorrent> {
+        self.inner.read().unwrap().torrents.get(&id).cloned()
+    }
+
+    pub fn count(&self) -> usize {
+        self.inner.read().unwrap().torrents.len()
+    }
+
+    // Every label used by any torrent
+    pub fn labels(&self) -> BTreeSet<String> {
+        self.inner.read().unwrap().torrents.values().flat_map(|torrent| torrent.labels.iter().cloned()).collect()
+    }
+
+    // All torrents ordered by id
+    pub fn torrents(&self) -> Vec<TransmissionTorrent> {
+        self.inner.read().unwrap().torrents.values().cloned().collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn torrent(id: i64, name: &str) -> TransmissionTorrent {
+        TransmissionTorrent { id, name: name.to_string(), ..Default::default() }
+    }
+
+    fn names(store: &TorrentStore) -> Vec<(i64, String)> {
+        store.torrents().into_iter().map(|torrent| (torrent.id, torrent.name)).collect()
+    }
+
+    #[test]
+    fn replace_populates_the_store() {
+        let store = TorrentStore::default();
+        assert!(!store.is_populated());
+        store.replace(vec![torrent(2, "b"), torrent(1, "a")]);
+        assert!(store.is_populated());
+        assert_eq!(names(&store), vec![(1, "a".to_string()), (2, "b".to_string())]);
+    }
+
+    #[test]
+    fn apply_merges_the_recently_active_torrents() {
+        let store = TorrentStore::default();
+        store.replace(vec![torrent(1, "a"), torrent(2, "b")]);
+        store.apply(vec![torrent(2, "b renamed"), torrent(3, "c")], &[]);
+        assert_eq!(names(&store), vec![(1, "a".to_string()), (2, "b renamed".to_string()), (3, "c".to_string())]);
+    }
+
+    #[test]
+    fn apply_drops_the_removed_torrents() {
+        let store = TorrentStore::default();
+        store.replace(vec![torrent(1, "a"), torrent(2, "b"), torrent(3, "c")]);
+        store.apply(vec![], &[1, 3, 42]);
+        assert_eq!(names(&store), vec![(2, "b".to_string())]);
+        assert_eq!(store.count(), 1);
+    }
+
+    #[test]
+    fn apply_keeps_a_torrent_removed_and_added_again() {
+        let store = TorrentStore::default();
+        store.replace(vec![torrent(1, "a")]);
+        store.apply(vec![torrent(1, "a again")], &[1]);
+        assert_eq!(names(&store), vec![(1, "a again".to_string())]);
+    }
+
+    #[test]
+    fn clear_forgets_everything() {
+        let store = TorrentStore::default();
+        store.replace(vec![torrent(1, "a")]);
+        store.clear();
+        assert!(!store.is_populated());
+        assert_eq!(store.count(), 0);
+    }
+
+    #[test]
+    fn every_change_bumps_the_generation() {
+        let store = TorrentStore::default();
+        let mut generations = vec![store.generation()];
+        store.replace(vec![torrent(1, "a")]);
+        generations.push(store.generation());
+        store.apply(vec![torrent(2, "b")], &[]);
+        generations.push(store.generation());
+        store.rename_path(1, "a", "a renamed");
+        generations.push(store.generation());
+        store.clear();
+        generations.push(store.generation());
+        assert!(generations.windows(2).all(|pair| pair[0] < pair[1]));
+        store.relocate(&[2], "/data", true);
+        assert_eq!(store.generation(), generations[4]);
+    }
+}
@@ -1,6 +1,8 @@
 use std::time::{Duration, UNIX_EPOCH};
 use chrono::{DateTime, Local, Utc};
 use crossterm::event::{KeyEvent, KeyModifiers};
+use ratatui::style::Stylize;
+use ratatui::text::Line;
 use crate::dto::transmission_dto::TransmissionTorrent;
 
 pub struct Util {}
@@ -39,4 +41,106 @@ impl Util {
         let datetime = DateTime::<Utc>::from(d).with_timezone(&Local);
         datetime.format("%Y-%m-%d %H:%M:%S").to_string()
     }
+
+    // Coarse duration keeping the two largest units, e.g. "2h 05m" or "42s"
+    pub fn print_duration(seconds: i64) -> String {
+        let seconds = seconds.max(0);
+        let (days, hours, minutes) = (seconds / 86_400, seconds / 3_600 % 24, seconds / 60 % 60);
+        if days > 0 {
+            format!("{days}d {hours:0>2}h")
+        } else if hours > 0 {
+            format!("{hours}h {minutes:0>2}m")
+        } else if minutes > 0 {
+            format!("{minutes}m {:0>2}s", seconds % 60)
+        } else {
+            format!("{seconds}s")
+        }
+    }
+
+    // Text gauge of `width` characters (e.g. "███░░░░░" for 0.375)
+    pub fn print_gauge(ratio: f64, width: usize) -> String {
+        let filled = ((ratio.clamp(0f64, 1f64) * width as f64).round() as usize).min(width);
+        format!("{}{}", "\u{2588}".repeat(filled), "\u{2591}".repeat(width - filled))
+    }
+
+    // A name for `torrent-rename-path`, the error tells what is wrong with it
+    pub fn validate_file_name(name: &str) -> Result<String, String> {
+        let name = name.trim();
+        if name.is_empty() {
+            Err("Expected a name".to_string())
+        } else if name.contains(['/', '\\']) {
+            Err("A name cannot contain a path separator".to_string())
+        } else if name == "." || name == ".." {
+            Err(format!("`{}` is not a name", name))
+        } else {
+            Ok(name.to_string())
+        }
+    }
+
+    // Seconds since the unix epoch
+    pub fn now_epoch() -> i64 {
+        UNIX_EPOCH.elapsed().map(|elapsed| elapsed.as_secs() as i64).unwrap_or_default()
+    }
+
+    // At most `height` lines, the last one telling how many did not fit
+    pub fn cap_lines(mut lines: Vec<Line<'static>>, height: usize) -> Vec<Line<'static>> {
+        if lines.len() > height {
+            let hidden = lines.len() - height.saturating_sub(1);
+            lines.truncate(height.saturating_sub(1));
+            if height > 0 {
+                lines.push(Line::from(format!("+{} more", hidden).dim()));
+            }
+        }
+        lines
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn validate_file_name_trims_a_valid_name() {
+        assert_eq!(Util::validate_file_name("  debian.iso "), Ok("debian.iso".to_string()));
+        assert_eq!(Util::validate_file_name("..hidden"), Ok("..hidden".to_string()));
+    }
+
+    #[test]
+    fn validate_file_name_rejects_an_empty_name() {
+        assert!(Util::validate_file_name("").is_err());
+        assert!(Util::validate_file_name("   ").is_err());
+    }
+
+    #[test]
+    fn validate_file_name_rejects_path_separators() {
+        assert!(Util::validate_file_name("dir/file").is_err());
+        assert!(Util::validate_file_name("/file").is_err());
+        assert!(Util::validate_file_name("dir\\file").is_err());
+    }
+
+    #[test]
+    fn validate_file_name_rejects_dot_and_dot_dot() {
+        assert!(Util::validate_file_name(".").is_err());
+        assert!(Util::validate_file_name("..").is_err());
+        assert!(Util::validate_file_name(" .. ").is_err());
+    }
+
+    fn lines(count: usize) -> Vec<Line<'static>> {
+        (0..count).map(|i| Line::from(i.to_string())).collect()
+    }
+
+    #[test]
+    fn cap_lines_keeps_lines_which_fit() {
+        assert_eq!(Util::cap_lines(lines(3), 3), lines(3));
+        assert_eq!(Util::cap_lines(lines(0), 0), lines(0));
+    }
+
+    #[test]
+    fn cap_lines_tells_how_many_did_not_fit() {
+        let capped = Util::cap_lines(lines(10), 4);
+        assert_eq!(capped.len(), 4);
+        assert_eq!(capped[2], Line::from("2"));
+        assert_eq!(capped[3].to_string(), "+7 more");
+        assert!(Util::cap_lines(lines(10), 0).is_empty());
+    }
 }